@@ -0,0 +1,182 @@
+// Custom ASCII art files
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use std::fs;
+use std::path::PathBuf;
+
+/// Marks the end of the art in .ans files, followed by the SAUCE record
+const SUB: u8 = 0x1a;
+
+/// Upper bound for a single cursor-forward run, far wider than any terminal
+const MAX_CURSOR_FORWARD: usize = 512;
+
+/// Code page 437 characters 0x80..=0xFF, the encoding of most .ans art
+#[rustfmt::skip]
+const CP437_HIGH: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', ' ',
+];
+
+/// Reads the art from `path` after expanding `~` and environment variables.
+/// `.ans` files get their SAUCE record stripped and are decoded as CP437
+/// unless they are valid UTF-8.
+pub fn read_art_file(path: &str) -> Option<String> {
+    let path = expand_path(path)?;
+    let bytes = fs::read(&path).ok()?;
+    let is_ans = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ans"));
+
+    let art = if is_ans {
+        let end = bytes.iter().position(|&b| b == SUB).unwrap_or(bytes.len());
+        let bytes = &bytes[..end];
+        let text = String::from_utf8(bytes.to_vec()).unwrap_or_else(|_| decode_cp437(bytes));
+        expand_cursor_forward(&text.replace("\r\n", "\n"))
+    } else {
+        String::from_utf8_lossy(&bytes).replace("\r\n", "\n")
+    };
+    Some(art)
+}
+
+/// Expands a leading `~` and `$VAR` / `${VAR}` references. Unset
+/// variables expand to nothing.
+pub fn expand_path(path: &str) -> Option<PathBuf> {
+    expand_path_with(path, |name| std::env::var(name).ok())
+}
+
+/// [`expand_path`] with variables looked up through `var`.
+fn expand_path_with(path: &str, var: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    let path = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            format!("{}{}", dirs::home_dir()?.display(), rest)
+        }
+        _ => path.to_string(),
+    };
+
+    let mut expanded = String::new();
+    let mut rest = path.as_str();
+    while let Some(pos) = rest.find('$') {
+        expanded.push_str(&rest[..pos]);
+        let tail = &rest[pos + 1..];
+        let (name, after) = match tail.strip_prefix('{') {
+            Some(braced) => match braced.split_once('}') {
+                Some((name, after)) => (name, after),
+                None => ("", tail),
+            },
+            None => {
+                let end = tail
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .unwrap_or(tail.len());
+                tail.split_at(end)
+            }
+        };
+        if name.is_empty() {
+            expanded.push('$');
+        } else {
+            expanded.push_str(&var(name).unwrap_or_default());
+        }
+        rest = after;
+    }
+    expanded.push_str(rest);
+    Some(PathBuf::from(expanded))
+}
+
+fn decode_cp437(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x80.. => CP437_HIGH[(b - 0x80) as usize],
+            _ => b as char,
+        })
+        .collect()
+}
+
+/// ANSI art often moves the cursor right (`ESC [ n C`) instead of printing
+/// spaces, which would make the art look narrower than it is.
+fn expand_cursor_forward(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find("\x1b[") {
+        result.push_str(&rest[..pos]);
+        let tail = &rest[pos + 2..];
+        let digits = tail
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(tail.len());
+        if tail[digits..].starts_with('C') {
+            // A missing or zero count moves one column, a count too large
+            // to parse is clamped like any other large one
+            let count = match tail[..digits].parse::<usize>() {
+                Ok(count) => count.clamp(1, MAX_CURSOR_FORWARD),
+                Err(_) if digits == 0 => 1,
+                Err(_) => MAX_CURSOR_FORWARD,
+            };
+            result.push_str(&" ".repeat(count));
+            rest = &tail[digits + 1..];
+        } else {
+            result.push_str("\x1b[");
+            rest = tail;
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_paths() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_path("~"), Some(home.clone()));
+        assert_eq!(expand_path("~/art.txt"), Some(home.join("art.txt")));
+        // Only a leading ~ followed by / is the home directory
+        assert_eq!(expand_path("~user/a"), Some(PathBuf::from("~user/a")));
+
+        let var = |name: &str| (name == "ART_DIR").then(|| "/opt/art".to_string());
+        assert_eq!(
+            expand_path_with("$ART_DIR/logo.ans", var),
+            Some(PathBuf::from("/opt/art/logo.ans"))
+        );
+        assert_eq!(
+            expand_path_with("${ART_DIR}s/a", var),
+            Some(PathBuf::from("/opt/arts/a"))
+        );
+        assert_eq!(
+            expand_path_with("/a/$UNSET/b", var),
+            Some(PathBuf::from("/a//b"))
+        );
+        assert_eq!(
+            expand_path_with("/a/$/b${x", var),
+            Some(PathBuf::from("/a/$/b${x"))
+        );
+    }
+
+    #[test]
+    fn decodes_cp437() {
+        assert_eq!(decode_cp437(b"ab \xdb\xb0\xc9\xcd\xbb"), "ab █░╔═╗");
+    }
+
+    #[test]
+    fn expands_cursor_forward() {
+        assert_eq!(expand_cursor_forward("a\x1b[3Cb"), "a   b");
+        // Missing and zero counts move one column
+        assert_eq!(expand_cursor_forward("a\x1b[Cb\x1b[0Cc"), "a b c");
+        assert_eq!(
+            expand_cursor_forward("\x1b[99999999999999999999C").len(),
+            MAX_CURSOR_FORWARD
+        );
+        assert_eq!(
+            expand_cursor_forward("\x1b[9999C").len(),
+            MAX_CURSOR_FORWARD
+        );
+        // Other sequences are kept
+        assert_eq!(expand_cursor_forward("\x1b[1;31mx"), "\x1b[1;31mx");
+    }
+}
@@ -0,0 +1,145 @@
+// Sound server and sound cards
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use sysinfo::System;
+
+#[derive(Serialize)]
+pub struct AudioInfo {
+    /// Running sound servers, e.g. `["PipeWire", "JACK"]`
+    pub servers: Vec<String>,
+    pub cards: Vec<String>,
+    pub default_sink: Option<String>,
+}
+
+impl AudioInfo {
+    pub fn detect(sys: &System) -> Self {
+        let processes: Vec<String> = sys
+            .processes()
+            .values()
+            .map(|p| p.name().to_string_lossy().into_owned())
+            .collect();
+        let running = |names: &[&str]| processes.iter().any(|p| names.contains(&p.as_str()));
+        let runtime_dir = std::env::var("XDG_RUNTIME_DIR").map(PathBuf::from).ok();
+        let socket = |name: &str| runtime_dir.as_ref().is_some_and(|d| d.join(name).exists());
+
+        let pipewire = socket("pipewire-0") || running(&["pipewire"]);
+        // PipeWire also provides the PulseAudio socket through pipewire-pulse
+        let pulseaudio = !pipewire && (running(&["pulseaudio"]) || socket("pulse/native"));
+        let jack = running(&["jackd", "jackdbus"]);
+
+        let cards = read_cards();
+        let mut servers = Vec::new();
+        if pipewire {
+            servers.push("PipeWire".to_string());
+        }
+        if pulseaudio {
+            servers.push("PulseAudio".to_string());
+        }
+        if jack {
+            servers.push("JACK".to_string());
+        }
+        if servers.is_empty() && !cards.is_empty() {
+            servers.push("ALSA".to_string());
+        }
+
+        let default_sink = if pipewire {
+            read_wireplumber_sink()
+        } else if pulseaudio {
+            read_pulse_sink()
+        } else {
+            None
+        };
+
+        AudioInfo {
+            servers,
+            cards,
+            default_sink,
+        }
+    }
+}
+
+fn read_cards() -> Vec<String> {
+    parse_cards(&fs::read_to_string("/proc/asound/cards").unwrap_or_default())
+}
+
+/// Parses /proc/asound/cards:
+/// ` 0 [PCH            ]: HDA-Intel - HDA Intel PCH`
+fn parse_cards(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter(|line| line.trim_start().starts_with(|c: char| c.is_ascii_digit()))
+        .filter_map(|line| {
+            let (_, description) = line.split_once("]: ")?;
+            let name = description
+                .split_once(" - ")
+                .map_or(description, |(_, name)| name);
+            Some(name.trim().to_string())
+        })
+        .collect()
+}
+
+/// WirePlumber stores the user's choice in its state directory.
+fn read_wireplumber_sink() -> Option<String> {
+    let state = dirs::state_dir()?.join("wireplumber/default-nodes");
+    parse_wireplumber_sink(&fs::read_to_string(state).ok()?)
+}
+
+fn parse_wireplumber_sink(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        line.strip_prefix("default.configured.audio.sink=")
+            .map(|sink| sink.trim().to_string())
+            .filter(|sink| !sink.is_empty())
+    })
+}
+
+/// PulseAudio writes `<machine-id>-default-sink` into its config directory.
+fn read_pulse_sink() -> Option<String> {
+    let dir = dirs::config_dir()?.join("pulse");
+    let entries = fs::read_dir(&dir).ok()?;
+    let path = entries.flatten().map(|e| e.path()).find(|p| {
+        p.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.ends_with("-default-sink"))
+    })?;
+    read_trimmed(&path)
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sound_cards() {
+        let content = concat!(
+            " 0 [PCH            ]: HDA-Intel - HDA Intel PCH\n",
+            "                      HDA Intel PCH at 0xf7f10000 irq 32\n",
+            " 1 [NVidia         ]: HDA-Intel - HDA NVidia\n",
+        );
+        assert_eq!(parse_cards(content), ["HDA Intel PCH", "HDA NVidia"]);
+        assert!(parse_cards("--- no soundcards ---\n").is_empty());
+    }
+
+    #[test]
+    fn parses_wireplumber_default_sink() {
+        let content = concat!(
+            "[default-nodes]\n",
+            "default.configured.audio.source=alsa_input.mic\n",
+            "default.configured.audio.sink=alsa_output.pci-0000_00_1f.3\n",
+        );
+        assert_eq!(
+            parse_wireplumber_sink(content).as_deref(),
+            Some("alsa_output.pci-0000_00_1f.3")
+        );
+        assert_eq!(parse_wireplumber_sink("[default-nodes]\n"), None);
+    }
+}
@@ -0,0 +1,174 @@
+// Battery and power supply information
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use crate::sysfs::{read_attr, read_num};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+#[derive(Serialize)]
+pub struct BatteryInfo {
+    pub name: String,
+    pub capacity: Option<u8>,
+    pub status: String,
+    /// Seconds until empty while discharging, or until full while charging
+    pub time_remaining: Option<u64>,
+    /// Full charge capacity relative to the design capacity, in percent
+    pub health: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct PowerInfo {
+    pub batteries: Vec<BatteryInfo>,
+    /// `None` when the machine has no AC adapter entry
+    pub ac_online: Option<bool>,
+}
+
+pub fn get_power() -> PowerInfo {
+    let mut batteries = Vec::new();
+    let mut ac_online = None;
+
+    let mut entries: Vec<_> = fs::read_dir(POWER_SUPPLY_DIR)
+        .map(|dir| dir.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    entries.sort();
+
+    for path in entries {
+        match read_attr(&path, "type").as_deref() {
+            Some("Battery") => {
+                // Skip batteries of peripherals such as wireless mice
+                if read_attr(&path, "scope").as_deref() == Some("Device") {
+                    continue;
+                }
+                batteries.push(read_battery(&path));
+            }
+            Some("Mains") => {
+                let online = read_attr(&path, "online").as_deref() == Some("1");
+                ac_online = Some(ac_online.unwrap_or(false) || online);
+            }
+            _ => {}
+        }
+    }
+
+    PowerInfo {
+        batteries,
+        ac_online,
+    }
+}
+
+fn read_battery(path: &Path) -> BatteryInfo {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("BAT")
+        .to_string();
+    let status = read_attr(path, "status").unwrap_or_else(|| "Unknown".to_string());
+
+    // Batteries report either energy (µWh, µW) or charge (µAh, µA)
+    let (now, full, design, rate) = if path.join("energy_now").exists() {
+        (
+            read_num(path, "energy_now"),
+            read_num(path, "energy_full"),
+            read_num(path, "energy_full_design"),
+            read_num(path, "power_now"),
+        )
+    } else {
+        (
+            read_num(path, "charge_now"),
+            read_num(path, "charge_full"),
+            read_num(path, "charge_full_design"),
+            read_num(path, "current_now"),
+        )
+    };
+
+    let capacity = read_num(path, "capacity")
+        .map(|c| c.min(100) as u8)
+        .or_else(|| match (now, full) {
+            (Some(now), Some(full)) if full > 0 => Some((now * 100 / full).min(100) as u8),
+            _ => None,
+        });
+
+    let time_remaining = match status.as_str() {
+        "Discharging" => read_num(path, "time_to_empty_now").or_else(|| match (now, rate) {
+            (Some(now), Some(rate)) if rate > 0 => Some(now * 3600 / rate),
+            _ => None,
+        }),
+        "Charging" => read_num(path, "time_to_full_now").or_else(|| match (now, full, rate) {
+            (Some(now), Some(full), Some(rate)) if rate > 0 => {
+                Some(full.saturating_sub(now) * 3600 / rate)
+            }
+            _ => None,
+        }),
+        _ => None,
+    };
+
+    let health = match (full, design) {
+        (Some(full), Some(design)) if design > 0 => Some(full as f64 * 100.0 / design as f64),
+        _ => None,
+    };
+
+    BatteryInfo {
+        name,
+        capacity,
+        status,
+        time_remaining,
+        health,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::TempDir;
+
+    /// A `BAT0` directory with the given attributes.
+    fn battery_dir(name: &str, attrs: &[(&str, &str)]) -> TempDir {
+        let dir = TempDir::new(&format!("battery-{}", name));
+        fs::create_dir(dir.join("BAT0")).unwrap();
+        for (attr, value) in attrs {
+            fs::write(dir.join("BAT0").join(attr), format!("{}\n", value)).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn derives_capacity_and_time_from_charge() {
+        let dir = battery_dir(
+            "charge",
+            &[
+                ("status", "Discharging"),
+                ("charge_now", "2000000"),
+                ("charge_full", "4000000"),
+                ("charge_full_design", "5000000"),
+                ("current_now", "1000000"),
+            ],
+        );
+        let battery = read_battery(&dir.join("BAT0"));
+
+        assert_eq!(battery.name, "BAT0");
+        assert_eq!(battery.capacity, Some(50));
+        assert_eq!(battery.time_remaining, Some(2 * 3600));
+        assert_eq!(battery.health, Some(80.0));
+    }
+
+    #[test]
+    fn prefers_reported_capacity_and_energy() {
+        let dir = battery_dir(
+            "energy",
+            &[
+                ("status", "Charging"),
+                ("capacity", "73"),
+                ("energy_now", "30000000"),
+                ("energy_full", "50000000"),
+                ("power_now", "10000000"),
+            ],
+        );
+        let battery = read_battery(&dir.join("BAT0"));
+
+        assert_eq!(battery.capacity, Some(73));
+        assert_eq!(battery.time_remaining, Some(2 * 3600));
+        assert_eq!(battery.health, None);
+    }
+}
@@ -0,0 +1,117 @@
+// Init system, firmware and bootloader
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+const EFI_DIR: &str = "/sys/firmware/efi";
+const EFIVARS_DIR: &str = "/sys/firmware/efi/efivars";
+const GLOBAL_GUID: &str = "8be4df61-93ca-11d2-aa0d-e0293f2b5c8c";
+const LOADER_GUID: &str = "4a67b082-0a4c-41cf-b6c7-440b29bb8c4f";
+
+#[derive(Serialize)]
+pub struct BootInfo {
+    pub init: Option<String>,
+    pub uefi: bool,
+    /// `None` when the state cannot be read, e.g. on BIOS systems
+    pub secure_boot: Option<bool>,
+    pub bootloader: Option<String>,
+}
+
+impl BootInfo {
+    pub fn detect() -> Self {
+        let uefi = Path::new(EFI_DIR).exists();
+        BootInfo {
+            init: detect_init(),
+            uefi,
+            secure_boot: if uefi { read_secure_boot() } else { None },
+            bootloader: if uefi { detect_bootloader() } else { None },
+        }
+    }
+}
+
+fn detect_init() -> Option<String> {
+    let comm = fs::read_to_string("/proc/1/comm").ok()?.trim().to_string();
+    let name = match comm.as_str() {
+        "systemd" => "systemd",
+        "runit" | "runit-init" => "runit",
+        "s6-svscan" => "s6",
+        "dinit" => "dinit",
+        "openrc-init" => "OpenRC",
+        "shepherd" => "GNU Shepherd",
+        // A generic /sbin/init: look for the service manager's runtime state
+        "init" => {
+            if Path::new("/run/systemd/system").exists() {
+                "systemd"
+            } else if Path::new("/run/openrc").exists() {
+                "OpenRC"
+            } else if Path::new("/run/runit").exists() || Path::new("/etc/runit/1").exists() {
+                "runit"
+            } else if Path::new("/run/s6").exists() {
+                "s6"
+            } else {
+                "SysVinit"
+            }
+        }
+        // Containers often run an ordinary program as PID 1
+        other => return Some(other.to_string()),
+    };
+    Some(name.to_string())
+}
+
+/// Reads an EFI variable without its leading 4-byte attribute field.
+fn read_efivar(name: &str, guid: &str) -> Option<Vec<u8>> {
+    let data = fs::read(Path::new(EFIVARS_DIR).join(format!("{}-{}", name, guid))).ok()?;
+    (data.len() > 4).then(|| data[4..].to_vec())
+}
+
+fn read_secure_boot() -> Option<bool> {
+    read_efivar("SecureBoot", GLOBAL_GUID).map(|data| data[0] == 1)
+}
+
+fn detect_bootloader() -> Option<String> {
+    // systemd-boot and other loaders implementing the Boot Loader Interface
+    if let Some(info) = read_efivar("LoaderInfo", LOADER_GUID) {
+        return decode_utf16(&info);
+    }
+
+    // Otherwise use the description of the boot entry the firmware started,
+    // e.g. "GRUB", "ubuntu" or "rEFInd Boot Manager"
+    let current = read_efivar("BootCurrent", GLOBAL_GUID)?;
+    let index = u16::from_le_bytes([current[0], *current.get(1)?]);
+    let entry = read_efivar(&format!("Boot{:04X}", index), GLOBAL_GUID)?;
+    // EFI_LOAD_OPTION: u32 attributes, u16 file path length, then the description
+    entry.get(6..).and_then(decode_utf16)
+}
+
+/// Decodes a NUL-terminated UTF-16LE string.
+fn decode_utf16(data: &[u8]) -> Option<String> {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect();
+    let text = String::from_utf16_lossy(&units).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_nul_terminated_utf16() {
+        let encode =
+            |text: &str| -> Vec<u8> { text.encode_utf16().flat_map(u16::to_le_bytes).collect() };
+        assert_eq!(
+            decode_utf16(&encode("systemd-boot 255\0garbage")).as_deref(),
+            Some("systemd-boot 255")
+        );
+        assert_eq!(decode_utf16(&encode("  \0")), None);
+        // A trailing odd byte is ignored
+        let mut data = encode("GRUB");
+        data.push(0x41);
+        assert_eq!(decode_utf16(&data).as_deref(), Some("GRUB"));
+    }
+}
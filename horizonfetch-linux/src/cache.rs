@@ -0,0 +1,252 @@
+// On-disk cache for slow probes
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const BOOT_ID: &str = "/proc/sys/kernel/random/boot_id";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Use fresh entries, store new results
+    #[default]
+    Normal,
+    /// Neither read nor write the cache
+    Disabled,
+    /// Ignore existing entries but store new results
+    Refresh,
+}
+
+/// What an entry depends on besides its age. The entry is dropped as soon
+/// as any hint differs from when it was stored.
+pub enum Hint<'a> {
+    /// The current boot, for hardware that only changes across reboots
+    Boot,
+    /// Modification time of a file or directory, e.g. a package database
+    Mtime(&'a Path),
+    /// Any other value the result depends on
+    Value(&'a str),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    created: u64,
+    hints: Vec<String>,
+    value: T,
+}
+
+/// Results live in `$XDG_CACHE_HOME/horizonfetch/<probe>.json`.
+pub struct Cache {
+    dir: Option<PathBuf>,
+    mode: CacheMode,
+    boot_id: Option<String>,
+    /// Probe timings for `--debug`
+    timings: Mutex<Vec<String>>,
+}
+
+impl Cache {
+    pub fn new(mode: CacheMode) -> Self {
+        Cache {
+            dir: dirs::cache_dir().map(|dir| dir.join("horizonfetch")),
+            mode,
+            boot_id: fs::read_to_string(BOOT_ID)
+                .ok()
+                .map(|id| id.trim().to_string()),
+            timings: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the cached result of `probe` if it is younger than `ttl` and
+    /// all hints still match, otherwise runs it and stores the result.
+    pub fn get<T, F>(&self, probe: &str, ttl: Duration, hints: &[Hint], run: F) -> T
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> T,
+    {
+        self.fetch(probe, ttl, hints, run, |_| true)
+    }
+
+    /// Like [`Cache::get`], but a `None` result is never stored, so a probe
+    /// that failed or timed out runs again next time.
+    pub fn get_opt<T, F>(&self, probe: &str, ttl: Duration, hints: &[Hint], run: F) -> Option<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Option<T>,
+    {
+        self.fetch(probe, ttl, hints, run, Option::is_some)
+    }
+
+    fn fetch<T, F>(
+        &self,
+        probe: &str,
+        ttl: Duration,
+        hints: &[Hint],
+        run: F,
+        keep: impl Fn(&T) -> bool,
+    ) -> T
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> T,
+    {
+        let start = Instant::now();
+        let hints = self.resolve(hints);
+        let path = self
+            .dir
+            .as_ref()
+            .filter(|_| self.mode != CacheMode::Disabled)
+            .map(|dir| dir.join(format!("{}.json", probe)));
+
+        if self.mode == CacheMode::Normal {
+            let cached = path.as_deref().and_then(|p| read_entry(p, ttl, &hints));
+            if let Some(value) = cached.filter(&keep) {
+                self.record(probe, "warm", start);
+                return value;
+            }
+        }
+
+        let value = run();
+        if let Some(path) = path.filter(|_| keep(&value)) {
+            write_entry(&path, &hints, &value);
+        }
+        self.record(probe, "cold", start);
+        value
+    }
+
+    /// Timing lines collected so far, e.g. `gpu: 41.20 ms (cold)`.
+    pub fn timings(&self) -> Vec<String> {
+        self.timings.lock().map(|t| t.clone()).unwrap_or_default()
+    }
+
+    fn resolve(&self, hints: &[Hint]) -> Vec<String> {
+        hints
+            .iter()
+            .map(|hint| match hint {
+                Hint::Boot => format!("boot:{}", self.boot_id.as_deref().unwrap_or("")),
+                Hint::Mtime(path) => format!("mtime:{}:{}", path.display(), mtime(path)),
+                Hint::Value(value) => format!("value:{}", value),
+            })
+            .collect()
+    }
+
+    fn record(&self, probe: &str, state: &str, start: Instant) {
+        let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+        if let Ok(mut timings) = self.timings.lock() {
+            timings.push(format!("{}: {:.2} ms ({})", probe, elapsed, state));
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Seconds since the epoch, 0 when the path does not exist.
+fn mtime(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs())
+}
+
+fn read_entry<T: DeserializeOwned>(path: &Path, ttl: Duration, hints: &[String]) -> Option<T> {
+    let content = fs::read_to_string(path).ok()?;
+    let entry: Entry<T> = serde_json::from_str(&content).ok()?;
+    let age = now().checked_sub(entry.created)?;
+    (age < ttl.as_secs() && entry.hints == hints).then_some(entry.value)
+}
+
+fn write_entry<T: Serialize>(path: &Path, hints: &[String], value: &T) {
+    let entry = Entry {
+        created: now(),
+        hints: hints.to_vec(),
+        value,
+    };
+    let Ok(content) = serde_json::to_string(&entry) else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    // Write to a temporary file first so a concurrent run never reads a
+    // half-written entry. Failing to write is harmless.
+    let tmp = path.with_extension(format!("json.{}", std::process::id()));
+    if fs::write(&tmp, content).is_ok() && fs::rename(&tmp, path).is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::TempDir;
+    use std::cell::Cell;
+
+    /// The cache directory lives as long as the returned `TempDir`.
+    fn cache(name: &str, mode: CacheMode) -> (TempDir, Cache) {
+        let tmp = TempDir::new(&format!("cache-{}", name));
+        let cache = Cache {
+            dir: Some(tmp.join("cache")),
+            mode,
+            boot_id: Some("boot-a".to_string()),
+            timings: Mutex::new(Vec::new()),
+        };
+        (tmp, cache)
+    }
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn reuses_entries_until_a_hint_changes() {
+        let (_tmp, mut cache) = cache("hints", CacheMode::Normal);
+        let runs = Cell::new(0);
+        let probe = |cache: &Cache, value: &str| {
+            cache.get("probe", TTL, &[Hint::Boot, Hint::Value(value)], || {
+                runs.set(runs.get() + 1);
+                runs.get()
+            })
+        };
+
+        assert_eq!(probe(&cache, "x"), 1);
+        assert_eq!(probe(&cache, "x"), 1);
+        assert_eq!(probe(&cache, "y"), 2);
+        cache.boot_id = Some("boot-b".to_string());
+        assert_eq!(probe(&cache, "y"), 3);
+
+        cache.mode = CacheMode::Refresh;
+        assert_eq!(probe(&cache, "y"), 4);
+        cache.mode = CacheMode::Normal;
+        assert_eq!(probe(&cache, "y"), 4);
+        assert_eq!(cache.timings().len(), 6);
+    }
+
+    #[test]
+    fn expired_entries_are_ignored() {
+        let (_tmp, cache) = cache("ttl", CacheMode::Normal);
+        assert_eq!(cache.get("probe", TTL, &[], || 1), 1);
+        assert_eq!(cache.get("probe", Duration::ZERO, &[], || 2), 2);
+    }
+
+    #[test]
+    fn failed_probes_are_not_stored() {
+        let (_tmp, cache) = cache("opt", CacheMode::Normal);
+        assert_eq!(cache.get_opt::<u32, _>("probe", TTL, &[], || None), None);
+        assert_eq!(cache.get_opt("probe", TTL, &[], || Some(1)), Some(1));
+        assert_eq!(cache.get_opt("probe", TTL, &[], || Some(2)), Some(1));
+    }
+
+    #[test]
+    fn disabled_cache_never_writes() {
+        let (_tmp, cache) = cache("disabled", CacheMode::Disabled);
+        assert_eq!(cache.get("probe", TTL, &[], || 1), 1);
+        assert_eq!(cache.get("probe", TTL, &[], || 2), 2);
+        assert!(!cache.dir.unwrap().exists());
+    }
+}
@@ -0,0 +1,257 @@
+// Control group resource limits
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use crate::cpu::parse_cpulist;
+use crate::sysfs::{read_attr, read_num};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+#[derive(Default, Serialize)]
+pub struct CgroupLimits {
+    pub memory_limit: Option<u64>,
+    /// Memory charged to the cgroup minus reclaimable file cache
+    pub memory_usage: Option<u64>,
+    /// CPU bandwidth limit in CPUs, e.g. 2.5 for `250000 100000`
+    pub cpu_quota: Option<f64>,
+    /// Number of CPUs the cgroup is allowed to run on
+    pub cpuset: Option<usize>,
+}
+
+impl CgroupLimits {
+    pub fn read() -> Self {
+        let content = fs::read_to_string("/proc/self/cgroup").unwrap_or_default();
+        let mut v2_path = None;
+        let mut v1_paths = Vec::new();
+
+        for line in content.lines() {
+            let mut parts = line.splitn(3, ':');
+            let (Some(id), Some(controllers), Some(path)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+            if id == "0" && controllers.is_empty() {
+                v2_path = Some(path.to_string());
+            } else {
+                for controller in controllers.split(',') {
+                    v1_paths.push((controller.to_string(), path.to_string()));
+                }
+            }
+        }
+
+        if !v1_paths.is_empty() {
+            let v1_dir = |name: &str| {
+                v1_paths
+                    .iter()
+                    .find(|(controller, _)| controller == name)
+                    .map(|(controller, path)| {
+                        resolve(&Path::new(CGROUP_ROOT).join(controller), path)
+                    })
+            };
+            read_v1(
+                Path::new(CGROUP_ROOT),
+                v1_dir("memory"),
+                v1_dir("cpu"),
+                v1_dir("cpuset"),
+            )
+        } else if let Some(path) = v2_path {
+            let root = Path::new(CGROUP_ROOT);
+            read_v2(root, &resolve(root, &path))
+        } else {
+            CgroupLimits::default()
+        }
+    }
+
+    /// CPUs the process may effectively use, if lower than `host_threads`.
+    pub fn effective_cpus(&self, host_threads: usize) -> Option<f64> {
+        let cpuset = self.cpuset.map(|c| c as f64);
+        let limit = match (self.cpu_quota, cpuset) {
+            (Some(quota), Some(cpuset)) => quota.min(cpuset),
+            (Some(limit), None) | (None, Some(limit)) => limit,
+            (None, None) => return None,
+        };
+        Some(limit).filter(|&l| l < host_threads as f64)
+    }
+
+    /// Memory limit, if lower than `host_total`.
+    pub fn effective_memory(&self, host_total: u64) -> Option<u64> {
+        self.memory_limit.filter(|&l| l < host_total)
+    }
+}
+
+/// Maps a path from /proc/self/cgroup onto the mounted hierarchy. Without a
+/// cgroup namespace the path may not exist below the mount, so fall back to
+/// the mount root.
+fn resolve(mount: &Path, path: &str) -> PathBuf {
+    let dir = mount.join(path.trim_start_matches('/'));
+    if dir.exists() {
+        dir
+    } else {
+        mount.to_path_buf()
+    }
+}
+
+fn read_v2(root: &Path, leaf: &Path) -> CgroupLimits {
+    let memory_limit = min_up_tree(leaf, root, |dir| read_num(dir, "memory.max"));
+    let memory_usage = read_num(leaf, "memory.current")
+        .map(|current| current.saturating_sub(read_stat(leaf, "inactive_file").unwrap_or(0)));
+
+    let cpu_quota = min_up_tree_f64(leaf, root, |dir| {
+        let content = fs::read_to_string(dir.join("cpu.max")).ok()?;
+        let mut parts = content.split_whitespace();
+        let quota: f64 = parts.next()?.parse().ok()?;
+        let period: f64 = parts.next()?.parse().ok()?;
+        Some(quota / period).filter(|_| period > 0.0)
+    });
+
+    CgroupLimits {
+        memory_limit,
+        memory_usage,
+        cpu_quota,
+        cpuset: read_cpuset(leaf, "cpuset.cpus.effective"),
+    }
+}
+
+/// `root` holds one hierarchy per controller, e.g. `<root>/memory`.
+fn read_v1(
+    root: &Path,
+    memory: Option<PathBuf>,
+    cpu: Option<PathBuf>,
+    cpuset: Option<PathBuf>,
+) -> CgroupLimits {
+    let mut limits = CgroupLimits::default();
+
+    if let Some(dir) = memory {
+        let root = root.join("memory");
+        limits.memory_limit = min_up_tree(&dir, &root, |d| read_num(d, "memory.limit_in_bytes"));
+        limits.memory_usage = read_num(&dir, "memory.usage_in_bytes")
+            .map(|usage| usage.saturating_sub(read_stat(&dir, "total_inactive_file").unwrap_or(0)));
+    }
+
+    if let Some(dir) = cpu {
+        let root = root.join("cpu");
+        limits.cpu_quota = min_up_tree_f64(&dir, &root, |d| {
+            let quota: i64 = read_attr(d, "cpu.cfs_quota_us")?.parse().ok()?;
+            let period: i64 = read_attr(d, "cpu.cfs_period_us")?.parse().ok()?;
+            // A quota of -1 means unlimited
+            Some(quota as f64 / period as f64).filter(|_| quota > 0 && period > 0)
+        });
+    }
+
+    if let Some(dir) = cpuset {
+        limits.cpuset =
+            read_cpuset(&dir, "cpuset.effective_cpus").or_else(|| read_cpuset(&dir, "cpuset.cpus"));
+    }
+
+    limits
+}
+
+/// Walks from `leaf` up to `root` and returns the tightest limit found,
+/// since a parent cgroup can be stricter than the one the process is in.
+fn min_up_tree(leaf: &Path, root: &Path, read: impl Fn(&Path) -> Option<u64>) -> Option<u64> {
+    leaf.ancestors()
+        .take_while(|dir| dir.starts_with(root))
+        .filter_map(read)
+        .min()
+}
+
+fn min_up_tree_f64(leaf: &Path, root: &Path, read: impl Fn(&Path) -> Option<f64>) -> Option<f64> {
+    leaf.ancestors()
+        .take_while(|dir| dir.starts_with(root))
+        .filter_map(read)
+        .reduce(f64::min)
+}
+
+fn read_stat(dir: &Path, key: &str) -> Option<u64> {
+    let content = fs::read_to_string(dir.join("memory.stat")).ok()?;
+    content.lines().find_map(|line| {
+        let (name, value) = line.split_once(' ')?;
+        if name == key {
+            value.trim().parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+fn read_cpuset(dir: &Path, attr: &str) -> Option<usize> {
+    parse_cpulist(&read_attr(dir, attr)?)
+        .map(|cpus| cpus.len())
+        .filter(|&count| count > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::TempDir;
+
+    #[test]
+    fn reads_v2_limits_up_the_tree() {
+        let root = TempDir::with_files(
+            "cgroup-v2",
+            &[
+                ("user.slice/memory.max", "1073741824\n"),
+                ("user.slice/cpu.max", "max 100000\n"),
+                ("user.slice/app/memory.max", "max\n"),
+                ("user.slice/app/memory.current", "500000\n"),
+                (
+                    "user.slice/app/memory.stat",
+                    "anon 1\ninactive_file 100000\n",
+                ),
+                ("user.slice/app/cpu.max", "250000 100000\n"),
+                ("user.slice/app/cpuset.cpus.effective", "0-3\n"),
+            ],
+        );
+        let limits = read_v2(root.path(), &root.join("user.slice/app"));
+
+        // The parent's limit applies, "max" is no limit at all
+        assert_eq!(limits.memory_limit, Some(1073741824));
+        assert_eq!(limits.memory_usage, Some(400000));
+        assert_eq!(limits.cpu_quota, Some(2.5));
+        assert_eq!(limits.cpuset, Some(4));
+    }
+
+    #[test]
+    fn reads_v1_limits() {
+        let root = TempDir::with_files(
+            "cgroup-v1",
+            &[
+                ("memory/docker/memory.limit_in_bytes", "536870912\n"),
+                ("memory/docker/memory.usage_in_bytes", "300000\n"),
+                ("memory/docker/memory.stat", "total_inactive_file 100000\n"),
+                ("cpu/docker/cpu.cfs_quota_us", "-1\n"),
+                ("cpu/docker/cpu.cfs_period_us", "100000\n"),
+                ("cpuset/docker/cpuset.cpus", "0,2\n"),
+            ],
+        );
+        let limits = read_v1(
+            root.path(),
+            Some(root.join("memory/docker")),
+            Some(root.join("cpu/docker")),
+            Some(root.join("cpuset/docker")),
+        );
+
+        assert_eq!(limits.memory_limit, Some(536870912));
+        assert_eq!(limits.memory_usage, Some(200000));
+        // A quota of -1 is unlimited
+        assert_eq!(limits.cpu_quota, None);
+        assert_eq!(limits.cpuset, Some(2));
+    }
+
+    #[test]
+    fn effective_limits_only_when_lower_than_the_host() {
+        let limits = CgroupLimits {
+            memory_limit: Some(8 << 30),
+            cpu_quota: Some(1.5),
+            cpuset: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(limits.effective_cpus(8), Some(1.5));
+        assert_eq!(limits.effective_cpus(1), None);
+        assert_eq!(limits.effective_memory(16 << 30), Some(8 << 30));
+        assert_eq!(limits.effective_memory(4 << 30), None);
+    }
+}
@@ -0,0 +1,105 @@
+// Command line arguments
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use crate::cache::CacheMode;
+use crate::logos;
+
+const USAGE: &str = "\
+Usage: horizonfetch [options]
+
+Options:
+  -h, --help           Show this help and exit
+  -v, --version        Show the version and exit
+  -r, --redact         Mask usernames, hostnames, addresses and home paths
+  -j, --json           Print the gathered information as JSON
+      --no-cache       Run every probe without reading or writing the cache
+      --refresh-cache  Run every probe and store the fresh results
+      --debug          Print probe timings to stderr
+      --list-logos     List the built-in logos for the `logo` setting and exit
+";
+
+#[derive(Debug, Default)]
+pub struct Args {
+    pub redact: bool,
+    pub json: bool,
+    pub cache: CacheMode,
+    pub debug: bool,
+}
+
+pub enum Action {
+    Run(Args),
+    Exit,
+}
+
+/// Parses the process arguments. Only the exact spellings listed in the
+/// usage are accepted.
+pub fn parse() -> Action {
+    parse_from(std::env::args().skip(1)).unwrap_or_else(|arg| {
+        eprintln!("horizonfetch: unknown option '{}'\n", arg);
+        eprint!("{}", USAGE);
+        std::process::exit(2);
+    })
+}
+
+/// Returns the first unknown argument as the error.
+fn parse_from(argv: impl IntoIterator<Item = String>) -> Result<Action, String> {
+    let mut args = Args::default();
+
+    for arg in argv {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                return Ok(Action::Exit);
+            }
+            "-v" | "--version" => {
+                println!("horizonfetch {}", env!("CARGO_PKG_VERSION"));
+                return Ok(Action::Exit);
+            }
+            "--list-logos" => {
+                let detected = logos::detect().name;
+                for logo in logos::LOGOS.iter() {
+                    let marker = if logo.name == detected { " (auto)" } else { "" };
+                    println!("{}{}", logo.name, marker);
+                }
+                return Ok(Action::Exit);
+            }
+            "-r" | "--redact" => args.redact = true,
+            "-j" | "--json" => args.json = true,
+            "--no-cache" => args.cache = CacheMode::Disabled,
+            "--refresh-cache" => args.cache = CacheMode::Refresh,
+            "--debug" => args.debug = true,
+            _ => return Err(arg),
+        }
+    }
+
+    Ok(Action::Run(args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(argv: &[&str]) -> Result<Args, String> {
+        match parse_from(argv.iter().map(|a| a.to_string()))? {
+            Action::Run(args) => Ok(args),
+            Action::Exit => Err("exit".to_string()),
+        }
+    }
+
+    #[test]
+    fn parses_flags() {
+        let args = run(&["-r", "--json", "--debug", "--refresh-cache"]).unwrap();
+        assert!(args.redact && args.json && args.debug);
+        assert_eq!(args.cache, CacheMode::Refresh);
+        assert_eq!(run(&["--no-cache"]).unwrap().cache, CacheMode::Disabled);
+        assert_eq!(run(&[]).unwrap().cache, CacheMode::Normal);
+    }
+
+    #[test]
+    fn rejects_unknown_options() {
+        assert_eq!(run(&["--json", "--jsno"]).unwrap_err(), "--jsno");
+        for arg in ["json", "---json", "-json", "--j", "-debug"] {
+            assert_eq!(run(&[arg]).unwrap_err(), arg);
+        }
+    }
+}
@@ -1,64 +1,169 @@
 // Config module
 // AnmiTaliDev <anmitalidev@nuros.org>
 
+use crate::art;
+use crate::logos;
+use crate::privacy::Privacy;
+use crate::units::Units;
 use std::{fs, io};
 
-const DEFAULT_ASCII: &str = r#"
-       ___
-      (.. \
-      (<> |
-     / __  \
-    ( /  \ /|
-   _/\ __)/_)
-   \/-____\/
-"#;
-
 #[derive(Debug, Clone)]
 pub struct Config {
     pub ascii_art: String,
+    /// Image drawn instead of the art when the terminal supports graphics
+    pub logo_image: Option<String>,
+    pub logo_image_width: u16,
+    /// "halfblock", "braille" or "none" when no graphics protocol works
+    pub logo_image_fallback: String,
+    /// Logo placement: "side", "top", "right" or "none"
+    pub layout: String,
     pub color: String,
+    /// Colors for the `${c1}`..`${c6}` placeholders in the art
+    pub ascii_colors: Vec<String>,
     pub info_color: String,
     pub title_color: String,
     pub show_user: bool,
     pub show_os: bool,
     pub show_uptime: bool,
+    pub show_packages: bool,
+    pub show_boot_time: bool,
+    pub uptime_long: bool,
+    pub boot_time_format: String,
+    pub show_load: bool,
     pub show_shell: bool,
+    pub show_terminal: bool,
+    pub show_terminal_font: bool,
     pub show_de: bool,
     pub show_screen: bool,
+    pub show_monitors: bool,
+    pub show_theme: bool,
+    pub show_icons: bool,
+    pub show_cursor: bool,
+    pub show_font: bool,
+    pub show_host: bool,
     pub show_motherboard: bool,
+    pub show_bios: bool,
+    pub show_virtualization: bool,
+    pub show_boot: bool,
+    pub show_security: bool,
+    pub show_security_selinux: bool,
+    pub show_security_apparmor: bool,
+    pub show_security_encryption: bool,
+    pub show_security_lockdown: bool,
     pub show_cpu: bool,
+    pub show_cpu_cores: bool,
+    pub show_cpu_sockets: bool,
+    pub show_cpu_freq: bool,
+    pub show_cpu_temp: bool,
     pub show_gpu: bool,
+    pub show_vram: bool,
     pub show_ram: bool,
     pub show_swap: bool,
     pub show_locale: bool,
+    pub show_timezone: bool,
+    pub show_keyboard: bool,
     pub show_disk: bool,
+    pub show_storage: bool,
+    pub show_battery: bool,
+    pub show_audio: bool,
+    pub show_media: bool,
+    pub show_network: bool,
+    pub show_network_ipv6: bool,
+    pub show_network_mac: bool,
+    pub network_show_virtual: bool,
+    pub network_interfaces: Vec<String>,
+    pub network_ignore: Vec<String>,
+    pub show_toolchains: bool,
+    pub toolchains: Vec<String>,
     #[allow(dead_code)]
     pub show_ram_ext_info: bool,
     pub show_color_scheme: bool,
+    pub query_palette: bool,
+    pub cgroup_limits: bool,
+    pub privacy: Privacy,
+    pub ram_units: Units,
+    pub swap_units: Units,
+    pub disk_units: Units,
+    pub storage_units: Units,
+    pub vram_units: Units,
 }
 
 impl Default for Config {
     fn default() -> Self {
+        let logo = logos::detect();
         Config {
-            ascii_art: DEFAULT_ASCII.to_string(),
-            color: "34".to_string(),
+            ascii_art: logo.art.to_string(),
+            logo_image: None,
+            logo_image_width: 24,
+            logo_image_fallback: "halfblock".to_string(),
+            layout: "side".to_string(),
+            color: logo.color.to_string(),
+            ascii_colors: Vec::new(),
             info_color: "38;5;117".to_string(),
             title_color: "38;5;110".to_string(),
             show_user: true,
             show_os: true,
             show_uptime: true,
+            show_packages: true,
+            show_boot_time: true,
+            uptime_long: false,
+            boot_time_format: "%x %R".to_string(),
+            show_load: true,
             show_shell: true,
+            show_terminal: true,
+            show_terminal_font: true,
             show_de: true,
             show_screen: true,
+            show_monitors: true,
+            show_theme: true,
+            show_icons: true,
+            show_cursor: true,
+            show_font: true,
+            show_host: true,
             show_motherboard: true,
+            show_bios: false,
+            show_virtualization: true,
+            show_boot: true,
+            show_security: true,
+            show_security_selinux: true,
+            show_security_apparmor: true,
+            show_security_encryption: true,
+            show_security_lockdown: true,
             show_cpu: true,
+            show_cpu_cores: true,
+            show_cpu_sockets: true,
+            show_cpu_freq: true,
+            show_cpu_temp: true,
             show_gpu: true,
+            show_vram: true,
             show_ram: true,
             show_swap: true,
             show_locale: true,
+            show_timezone: true,
+            show_keyboard: true,
             show_disk: true,
+            show_storage: true,
+            show_battery: true,
+            show_audio: true,
+            show_media: false,
+            show_network: true,
+            show_network_ipv6: true,
+            show_network_mac: false,
+            network_show_virtual: false,
+            network_interfaces: Vec::new(),
+            network_ignore: default_network_ignore(),
+            show_toolchains: false,
+            toolchains: default_toolchains(),
             show_ram_ext_info: false,
             show_color_scheme: true,
+            query_palette: false,
+            cgroup_limits: true,
+            privacy: Privacy::default(),
+            ram_units: Units::Iec,
+            swap_units: Units::Iec,
+            disk_units: Units::Iec,
+            storage_units: Units::Iec,
+            vram_units: Units::Iec,
         }
     }
 }
@@ -66,11 +171,30 @@ impl Default for Config {
 impl Config {
     pub fn load(path: &str) -> io::Result<Self> {
         let content = fs::read_to_string(path)?;
-        let ascii_art = extract_ascii_art(&content)
-            .unwrap_or(DEFAULT_ASCII)
-            .to_string();
+        // Custom art wins under "auto", a named logo always wins
+        let logo_setting = extract_param(&content, "logo").unwrap_or("auto");
+        let custom_art = extract_param(&content, "ascii_file")
+            .and_then(art::read_art_file)
+            .or_else(|| extract_ascii_art(&content));
+        let (ascii_art, logo_color) = match (logo_setting, custom_art) {
+            ("auto", Some(art)) => (art, "34"),
+            (setting, _) => logos::select(setting)
+                .map_or((String::new(), "34"), |l| (l.art.to_string(), l.color)),
+        };
         let color = extract_param(&content, "ascii_color")
-            .unwrap_or("34")
+            .unwrap_or(logo_color)
+            .to_string();
+        let ascii_colors = extract_colors(&content, "ascii_colors");
+        let logo_image = extract_param(&content, "logo_image").map(String::from);
+        let logo_image_width = extract_param(&content, "logo_image_width")
+            .and_then(|w| w.parse().ok())
+            .filter(|&w| w > 0)
+            .unwrap_or(24);
+        let logo_image_fallback = extract_param(&content, "logo_image_fallback")
+            .unwrap_or("halfblock")
+            .to_string();
+        let layout = extract_param(&content, "layout")
+            .unwrap_or("side")
             .to_string();
         let info_color = extract_param(&content, "info_color")
             .unwrap_or("38;5;117")
@@ -82,39 +206,162 @@ impl Config {
         let show_user = extract_bool(&content, "show_user", true);
         let show_os = extract_bool(&content, "show_os", true);
         let show_uptime = extract_bool(&content, "show_uptime", true);
+        let show_packages = extract_bool(&content, "show_packages", true);
+        let show_boot_time = extract_bool(&content, "show_boot_time", true);
+        let uptime_long = extract_param(&content, "uptime_format") == Some("long");
+        let boot_time_format = extract_param(&content, "boot_time_format")
+            .unwrap_or("%x %R")
+            .to_string();
+        let show_load = extract_bool(&content, "show_load", true);
         let show_shell = extract_bool(&content, "show_shell", true);
+        let show_terminal = extract_bool(&content, "show_terminal", true);
+        let show_terminal_font = extract_bool(&content, "show_terminal_font", true);
         let show_de = extract_bool(&content, "show_de", true);
         let show_screen = extract_bool(&content, "show_screen", true);
+        let show_monitors = extract_bool(&content, "show_monitors", true);
+        let show_theme = extract_bool(&content, "show_theme", true);
+        let show_icons = extract_bool(&content, "show_icons", true);
+        let show_cursor = extract_bool(&content, "show_cursor", true);
+        let show_font = extract_bool(&content, "show_font", true);
+        let show_host = extract_bool(&content, "show_host", true);
         let show_motherboard = extract_bool(&content, "show_motherboard", true);
+        let show_bios = extract_bool(&content, "show_bios", false);
+        let show_virtualization = extract_bool(&content, "show_virtualization", true);
+        let show_boot = extract_bool(&content, "show_boot", true);
+        let show_security = extract_bool(&content, "show_security", true);
+        let show_security_selinux = extract_bool(&content, "show_security_selinux", true);
+        let show_security_apparmor = extract_bool(&content, "show_security_apparmor", true);
+        let show_security_encryption = extract_bool(&content, "show_security_encryption", true);
+        let show_security_lockdown = extract_bool(&content, "show_security_lockdown", true);
         let show_cpu = extract_bool(&content, "show_cpu", true);
+        let show_cpu_cores = extract_bool(&content, "show_cpu_cores", true);
+        let show_cpu_sockets = extract_bool(&content, "show_cpu_sockets", true);
+        let show_cpu_freq = extract_bool(&content, "show_cpu_freq", true);
+        let show_cpu_temp = extract_bool(&content, "show_cpu_temp", true);
         let show_gpu = extract_bool(&content, "show_gpu", true);
+        let show_vram = extract_bool(&content, "show_vram", true);
         let show_ram = extract_bool(&content, "show_ram", true);
         let show_swap = extract_bool(&content, "show_swap", true);
         let show_locale = extract_bool(&content, "show_locale", true);
+        let show_timezone = extract_bool(&content, "show_timezone", true);
+        let show_keyboard = extract_bool(&content, "show_keyboard", true);
         let show_disk = extract_bool(&content, "show_disk", true);
+        let show_storage = extract_bool(&content, "show_storage", true);
+        let show_battery = extract_bool(&content, "show_battery", true);
+        let show_audio = extract_bool(&content, "show_audio", true);
+        let show_media = extract_bool(&content, "show_media", false);
+        let show_network = extract_bool(&content, "show_network", true);
+        let show_network_ipv6 = extract_bool(&content, "show_network_ipv6", true);
+        let show_network_mac = extract_bool(&content, "show_network_mac", false);
+        let network_show_virtual = extract_bool(&content, "network_show_virtual", false);
+        let network_interfaces = extract_list(&content, "network_interfaces");
+        // An empty value ignores nothing, a missing one keeps the default
+        let network_ignore = match extract_param(&content, "network_ignore") {
+            Some(_) => extract_list(&content, "network_ignore"),
+            None => default_network_ignore(),
+        };
+        let show_toolchains = extract_bool(&content, "show_toolchains", false);
+        let toolchains = match extract_list(&content, "toolchains") {
+            list if list.is_empty() => default_toolchains(),
+            list => list,
+        };
         let show_ram_ext_info = extract_bool(&content, "show_ram_ext_info", false);
         let show_color_scheme = extract_bool(&content, "show_color_scheme", true);
+        let query_palette = extract_bool(&content, "query_palette", false);
+
+        // "cgroup" shows container limits where they are lower than the host values
+        let cgroup_limits = extract_param(&content, "resource_limits") != Some("host");
+
+        let privacy = Privacy {
+            redact: extract_bool(&content, "redact", false),
+            username: extract_bool(&content, "redact_username", true),
+            hostname: extract_bool(&content, "redact_hostname", true),
+            network: extract_bool(&content, "redact_network", true),
+            paths: extract_bool(&content, "redact_paths", true),
+            mac: extract_bool(&content, "redact_mac", true),
+        };
+
+        // Per-module unit overrides fall back to the global setting
+        let units = extract_units(&content, "units", Units::Iec);
+        let ram_units = extract_units(&content, "ram_units", units);
+        let swap_units = extract_units(&content, "swap_units", units);
+        let disk_units = extract_units(&content, "disk_units", units);
+        let storage_units = extract_units(&content, "storage_units", units);
+        let vram_units = extract_units(&content, "vram_units", units);
 
         Ok(Config {
             ascii_art,
+            logo_image,
+            logo_image_width,
+            logo_image_fallback,
+            layout,
             color,
+            ascii_colors,
             info_color,
             title_color,
             show_user,
             show_os,
             show_uptime,
+            show_packages,
+            show_boot_time,
+            uptime_long,
+            boot_time_format,
+            show_load,
             show_shell,
+            show_terminal,
+            show_terminal_font,
             show_de,
             show_screen,
+            show_monitors,
+            show_theme,
+            show_icons,
+            show_cursor,
+            show_font,
+            show_host,
             show_motherboard,
+            show_bios,
+            show_virtualization,
+            show_boot,
+            show_security,
+            show_security_selinux,
+            show_security_apparmor,
+            show_security_encryption,
+            show_security_lockdown,
             show_cpu,
+            show_cpu_cores,
+            show_cpu_sockets,
+            show_cpu_freq,
+            show_cpu_temp,
             show_gpu,
+            show_vram,
             show_ram,
             show_swap,
             show_locale,
+            show_timezone,
+            show_keyboard,
             show_disk,
+            show_storage,
+            show_battery,
+            show_audio,
+            show_media,
+            show_network,
+            show_network_ipv6,
+            show_network_mac,
+            network_show_virtual,
+            network_interfaces,
+            network_ignore,
+            show_toolchains,
+            toolchains,
             show_ram_ext_info,
             show_color_scheme,
+            query_palette,
+            cgroup_limits,
+            privacy,
+            ram_units,
+            swap_units,
+            disk_units,
+            storage_units,
+            vram_units,
         })
     }
 
@@ -131,10 +378,29 @@ impl Config {
     }
 }
 
-fn extract_ascii_art(content: &str) -> Option<&str> {
-    let start = content.find("{|")? + 2;
-    let end = content.find("|}")?;
-    Some(&content[start..end])
+/// The art starts at a line beginning with `{|`, so the delimiters can be
+/// mentioned in comments. It ends at the first `|}` that is not escaped as
+/// `\|}`, the escape produces a literal `|}`.
+fn extract_ascii_art(content: &str) -> Option<String> {
+    let mut offset = 0;
+    let start = content.split_inclusive('\n').find_map(|line| {
+        let line_start = offset;
+        offset += line.len();
+        let indent = line.len() - line.trim_start().len();
+        line.trim_start()
+            .starts_with("{|")
+            .then_some(line_start + indent + 2)
+    })?;
+    let mut search = start;
+    let end = loop {
+        let pos = search + content[search..].find("|}")?;
+        if content[..pos].ends_with('\\') {
+            search = pos + 2;
+        } else {
+            break pos;
+        }
+    };
+    Some(content[start..end].replace("\\|}", "|}"))
 }
 
 fn extract_param<'a>(content: &'a str, param: &str) -> Option<&'a str> {
@@ -143,10 +409,10 @@ fn extract_param<'a>(content: &'a str, param: &str) -> Option<&'a str> {
         if trimmed.is_empty() || trimmed.starts_with('#') {
             continue;
         }
-        if trimmed.starts_with(param) {
-            let parts: Vec<&str> = trimmed.splitn(2, '=').collect();
-            if parts.len() == 2 {
-                let value = parts[1].trim();
+        if let Some((key, value)) = trimmed.split_once('=') {
+            // Compare whole keys so "show_cpu" does not match "show_cpu_freq"
+            if key.trim() == param {
+                let value = value.trim();
                 if value.starts_with('"') && value.ends_with('"') && value.len() > 2 {
                     return Some(&value[1..value.len() - 1]);
                 } else if !value.is_empty() {
@@ -162,6 +428,51 @@ fn extract_bool(content: &str, param: &str, default: bool) -> bool {
     extract_param(content, param).map_or(default, |v| v == "true")
 }
 
+fn default_network_ignore() -> Vec<String> {
+    vec!["docker*".to_string(), "veth*".to_string()]
+}
+
+fn default_toolchains() -> Vec<String> {
+    [
+        "rustc", "cargo", "gcc", "clang", "python", "node", "go", "java",
+    ]
+    .iter()
+    .map(|t| t.to_string())
+    .collect()
+}
+
+/// Accepts `["34", "37"]` as well as `"34,37"`, dropping invalid codes.
+fn extract_colors(content: &str, param: &str) -> Vec<String> {
+    extract_param(content, param)
+        .map(|v| {
+            v.trim_start_matches('[')
+                .trim_end_matches(']')
+                .split(',')
+                .map(|c| c.trim().trim_matches('"').to_string())
+                .filter(|c| is_valid_ansi_code(c))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn extract_list(content: &str, param: &str) -> Vec<String> {
+    extract_param(content, param)
+        .map(|v| {
+            // `""` as a value is an empty list, not a list of one `""`
+            v.split(',')
+                .map(|item| item.trim().trim_matches('"').to_string())
+                .filter(|item| !item.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn extract_units(content: &str, param: &str, default: Units) -> Units {
+    extract_param(content, param)
+        .and_then(Units::parse)
+        .unwrap_or(default)
+}
+
 pub fn is_valid_ansi_code(code: &str) -> bool {
     if code.is_empty() {
         return false;
@@ -180,3 +491,56 @@ pub fn is_valid_ansi_code(code: &str) -> bool {
         _ => false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::TempDir;
+
+    #[test]
+    fn splits_lists() {
+        let content = "a=\"docker*, veth*,\"\nb=\"\"\nc=eth0\n";
+        assert_eq!(extract_list(content, "a"), ["docker*", "veth*"]);
+        assert!(extract_list(content, "b").is_empty());
+        assert_eq!(extract_list(content, "c"), ["eth0"]);
+        assert!(extract_list(content, "d").is_empty());
+    }
+
+    #[test]
+    fn network_ignore_defaults_match_the_shipped_config() {
+        let shipped = include_str!("../hf.conf");
+        let dir = TempDir::with_files("config", &[("hf.conf", "network_ignore=\"\"\n")]);
+        let emptied = Config::load(dir.join("hf.conf").to_str().unwrap()).unwrap();
+
+        assert_eq!(
+            Config::default().network_ignore,
+            extract_list(shipped, "network_ignore")
+        );
+        assert!(emptied.network_ignore.is_empty());
+        assert!(extract_list(shipped, "network_interfaces").is_empty());
+    }
+
+    #[test]
+    fn units_fall_back_to_the_default() {
+        let content = "units=\"si\"\nram_units=iec\ndisk_units=\"gb\"\n";
+        assert_eq!(extract_units(content, "units", Units::Iec), Units::Si);
+        assert_eq!(extract_units(content, "ram_units", Units::Si), Units::Iec);
+        assert_eq!(extract_units(content, "disk_units", Units::Si), Units::Si);
+        assert_eq!(extract_units(content, "vram_units", Units::Iec), Units::Iec);
+    }
+
+    #[test]
+    fn extracts_ascii_art() {
+        let content = "show_os=true\n{|\n /\\\n/  \\\n|}\nshow_cpu=true\n";
+        assert_eq!(
+            extract_ascii_art(content).as_deref(),
+            Some("\n /\\\n/  \\\n")
+        );
+        // An escaped \|} is part of the art
+        let content = "  {|  a \\|} b\nc|}\n";
+        assert_eq!(extract_ascii_art(content).as_deref(), Some("  a |} b\nc"));
+        // {| has to start a line, commented out art is ignored
+        assert_eq!(extract_ascii_art("#{|\nx\n#|}\n"), None);
+        assert_eq!(extract_ascii_art("{|\nunterminated\n"), None);
+    }
+}
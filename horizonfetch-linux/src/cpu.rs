@@ -0,0 +1,256 @@
+// CPU information
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use crate::sysfs::read_num;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use sysinfo::System;
+
+const CPU_DIR: &str = "/sys/devices/system/cpu";
+const HWMON_DIR: &str = "/sys/class/hwmon";
+
+/// hwmon drivers that report the CPU package temperature
+const CPU_HWMON_DRIVERS: [&str; 4] = ["k10temp", "coretemp", "zenpower", "cpu_thermal"];
+/// Sensor labels that describe the whole package rather than a single core
+const PACKAGE_LABELS: [&str; 3] = ["Tctl", "Tdie", "Package id 0"];
+
+#[derive(Serialize)]
+pub struct CpuInfo {
+    pub brand: String,
+    pub threads: usize,
+    pub cores: Option<usize>,
+    pub sockets: Option<usize>,
+    /// Performance and efficiency core counts on hybrid CPUs
+    pub hybrid: Option<(usize, usize)>,
+    pub max_freq_mhz: Option<u64>,
+    pub cur_freq_mhz: Option<u64>,
+    pub temperature: Option<f64>,
+}
+
+pub fn get_cpu(sys: &System) -> CpuInfo {
+    let brand = sys
+        .cpus()
+        .first()
+        .map(|cpu| cpu.brand().trim().to_string())
+        .filter(|b| !b.is_empty())
+        .unwrap_or_else(|| "Unknown".to_string());
+    let topology = read_topology();
+
+    let cores = if topology.is_empty() {
+        None
+    } else {
+        Some(count_cores(&topology, |_| true))
+    };
+    let sockets = if topology.is_empty() {
+        None
+    } else {
+        let packages: HashSet<_> = topology.iter().map(|t| t.package).collect();
+        Some(packages.len())
+    };
+
+    // Intel hybrid CPUs expose separate PMUs for P-cores and E-cores
+    let hybrid = match (
+        read_cpulist(Path::new("/sys/devices/cpu_core/cpus")),
+        read_cpulist(Path::new("/sys/devices/cpu_atom/cpus")),
+    ) {
+        (Some(p), Some(e)) => hybrid_cores(&topology, &p, &e),
+        _ => None,
+    };
+
+    let (max_freq_mhz, cur_freq_mhz) = read_frequencies();
+
+    CpuInfo {
+        brand,
+        threads: sys.cpus().len(),
+        cores,
+        sockets,
+        hybrid,
+        max_freq_mhz,
+        cur_freq_mhz,
+        temperature: read_package_temperature(),
+    }
+}
+
+struct CpuTopology {
+    cpu: usize,
+    package: u32,
+    core: u32,
+}
+
+fn read_topology() -> Vec<CpuTopology> {
+    let Some(cpus) = read_cpulist(&Path::new(CPU_DIR).join("online")) else {
+        return Vec::new();
+    };
+
+    cpus.into_iter()
+        .filter_map(|cpu| {
+            let dir = Path::new(CPU_DIR).join(format!("cpu{}/topology", cpu));
+            Some(CpuTopology {
+                cpu,
+                package: read_num(&dir, "physical_package_id")? as u32,
+                core: read_num(&dir, "core_id")? as u32,
+            })
+        })
+        .collect()
+}
+
+fn count_cores(topology: &[CpuTopology], filter: impl Fn(usize) -> bool) -> usize {
+    topology
+        .iter()
+        .filter(|t| filter(t.cpu))
+        .map(|t| (t.package, t.core))
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// P-core and E-core counts. Without a topology to count in both are
+/// unknown, so the plain core count is shown instead.
+fn hybrid_cores(
+    topology: &[CpuTopology],
+    p_cpus: &HashSet<usize>,
+    e_cpus: &HashSet<usize>,
+) -> Option<(usize, usize)> {
+    let p_cores = count_cores(topology, |cpu| p_cpus.contains(&cpu));
+    let e_cores = count_cores(topology, |cpu| e_cpus.contains(&cpu));
+    (p_cores > 0 && e_cores > 0).then_some((p_cores, e_cores))
+}
+
+/// Returns the highest maximum and the average current frequency in MHz.
+fn read_frequencies() -> (Option<u64>, Option<u64>) {
+    let policies: Vec<_> = fs::read_dir(Path::new(CPU_DIR).join("cpufreq"))
+        .map(|dir| {
+            dir.flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with("policy"))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let max = policies
+        .iter()
+        .filter_map(|p| read_num(p, "cpuinfo_max_freq"))
+        .max()
+        .map(|khz| khz / 1000);
+
+    let current: Vec<u64> = policies
+        .iter()
+        .filter_map(|p| read_num(p, "scaling_cur_freq"))
+        .collect();
+    let cur = if current.is_empty() {
+        None
+    } else {
+        Some(current.iter().sum::<u64>() / current.len() as u64 / 1000)
+    };
+
+    (max, cur)
+}
+
+fn read_package_temperature() -> Option<f64> {
+    let mut hwmons: Vec<_> = fs::read_dir(HWMON_DIR)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .collect();
+    hwmons.sort();
+
+    for hwmon in hwmons {
+        let name = fs::read_to_string(hwmon.join("name")).unwrap_or_default();
+        if !CPU_HWMON_DRIVERS.contains(&name.trim()) {
+            continue;
+        }
+
+        // Prefer a package-wide sensor, fall back to the first one
+        let labelled = (1..=32).find(|i| {
+            fs::read_to_string(hwmon.join(format!("temp{}_label", i)))
+                .is_ok_and(|label| PACKAGE_LABELS.contains(&label.trim()))
+        });
+        let index = labelled.unwrap_or(1);
+        if let Some(millidegrees) = read_num(&hwmon, &format!("temp{}_input", index)) {
+            return Some(millidegrees as f64 / 1000.0);
+        }
+    }
+    None
+}
+
+fn read_cpulist(path: &Path) -> Option<HashSet<usize>> {
+    parse_cpulist(&fs::read_to_string(path).ok()?)
+}
+
+/// Parses the kernel cpulist format, e.g. `0-3,8-11`.
+pub(crate) fn parse_cpulist(content: &str) -> Option<HashSet<usize>> {
+    let mut cpus = HashSet::new();
+    for range in content.trim().split(',').filter(|r| !r.is_empty()) {
+        match range.split_once('-') {
+            Some((start, end)) => {
+                let start: usize = start.parse().ok()?;
+                let end: usize = end.parse().ok()?;
+                cpus.extend(start..=end);
+            }
+            None => {
+                cpus.insert(range.parse().ok()?);
+            }
+        }
+    }
+    Some(cpus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topology(cpus: &[(usize, u32, u32)]) -> Vec<CpuTopology> {
+        cpus.iter()
+            .map(|&(cpu, package, core)| CpuTopology { cpu, package, core })
+            .collect()
+    }
+
+    #[test]
+    fn parses_cpulists() {
+        let cpus = parse_cpulist("0-3,8-9,12\n").unwrap();
+        let mut sorted: Vec<usize> = cpus.into_iter().collect();
+        sorted.sort();
+        assert_eq!(sorted, [0, 1, 2, 3, 8, 9, 12]);
+        assert_eq!(parse_cpulist("").unwrap().len(), 0);
+        assert!(parse_cpulist("0-x").is_none());
+    }
+
+    #[test]
+    fn counts_physical_cores() {
+        // Two packages with two cores each, SMT siblings share a core id
+        let topology = topology(&[
+            (0, 0, 0),
+            (1, 0, 1),
+            (2, 0, 0),
+            (3, 0, 1),
+            (4, 1, 0),
+            (5, 1, 1),
+        ]);
+        assert_eq!(count_cores(&topology, |_| true), 4);
+        assert_eq!(count_cores(&topology, |cpu| cpu < 2), 2);
+    }
+
+    #[test]
+    fn counts_hybrid_cores() {
+        // Two P-cores with SMT and two E-cores
+        let topology = topology(&[
+            (0, 0, 0),
+            (1, 0, 0),
+            (2, 0, 1),
+            (3, 0, 1),
+            (4, 0, 8),
+            (5, 0, 9),
+        ]);
+        let p_cpus = parse_cpulist("0-3").unwrap();
+        let e_cpus = parse_cpulist("4-5").unwrap();
+        assert_eq!(hybrid_cores(&topology, &p_cpus, &e_cpus), Some((2, 2)));
+        // No topology, no "0P+0E"
+        assert_eq!(hybrid_cores(&[], &p_cpus, &e_cpus), None);
+        assert_eq!(hybrid_cores(&topology, &p_cpus, &HashSet::new()), None);
+    }
+}
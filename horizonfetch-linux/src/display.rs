@@ -1,28 +1,169 @@
 // Display rendering
 // AnmiTaliDev <anmitalidev@nuros.org>
 
+use crate::cache::Cache;
 use crate::config::{is_valid_ansi_code, Config};
-use crate::system::SystemInfo;
+use crate::graphics;
+use crate::network::{matches_pattern, NetworkInterface};
+use crate::storage::StorageDevice;
+use crate::system::{format_local_time, SystemInfo};
+use crate::units::{format_size, format_size_pair, percent};
 use crossterm::{
     cursor::MoveTo,
     execute,
     style::Print,
     terminal::{Clear, ClearType},
 };
+use std::cell::Cell;
 use std::io::{self, stdout};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub struct Display {
     config: Config,
     info: SystemInfo,
+    /// Widest info line printed so far, places the logo in the right layout
+    info_width: Cell<usize>,
+    /// Row the cursor is on in the top layout, relative to the first line
+    row: Cell<u16>,
+}
+
+fn format_uptime(secs: u64, long: bool) -> String {
+    let minutes = secs / 60;
+    let hours = minutes / 60;
+    let days = hours / 24;
+
+    if !long {
+        return if days > 0 {
+            format!("{}d {}h {}m", days, hours % 24, minutes % 60)
+        } else {
+            format!("{}h {}m", hours % 24, minutes % 60)
+        };
+    }
+
+    let plural = |n: u64, unit: &str| {
+        if n == 1 {
+            format!("{} {}", n, unit)
+        } else {
+            format!("{} {}s", n, unit)
+        }
+    };
+    let mut parts = Vec::new();
+    if days > 0 {
+        parts.push(plural(days, "day"));
+    }
+    let hours = hours % 24;
+    if hours > 0 {
+        parts.push(plural(hours, "hour"));
+    }
+    parts.push(plural(minutes % 60, "minute"));
+    parts.join(", ")
+}
+
+fn format_duration(secs: u64) -> String {
+    let minutes = secs / 60;
+    if minutes >= 60 {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn format_cpu_count(cpus: f64) -> String {
+    if cpus.fract() == 0.0 {
+        format!("{}", cpus as u64)
+    } else {
+        format!("{:.1}", cpus)
+    }
+}
+
+/// Number of `${cN}` placeholders available in the art
+const ART_COLOR_SLOTS: usize = 6;
+
+/// Replaces `${c1}`..`${c6}` with color escapes and returns the rendered
+/// line together with the line without placeholders, for width
+/// measurement. Raw escape codes in the art pass through untouched.
+///
+/// `pen` holds the escapes active at the end of the previous line. Colors
+/// carry over like in neofetch and ANSI art, so a placeholder or escape
+/// only needs to appear where the color changes.
+fn expand_art_colors(line: &str, colors: &[&str], pen: &mut String) -> (String, String) {
+    let mut rendered = pen.clone();
+    let mut plain = String::new();
+    let mut rest = line;
+    while let Some(pos) = rest.find("${c") {
+        let (before, tail) = rest.split_at(pos);
+        rendered.push_str(before);
+        plain.push_str(before);
+        let slot = tail[3..]
+            .split_once('}')
+            .and_then(|(digits, after)| Some((digits.parse::<usize>().ok()?, after)))
+            .filter(|(n, _)| (1..=colors.len()).contains(n));
+        match slot {
+            Some((n, after)) => {
+                rendered.push_str(&format!("\x1b[0m\x1b[{}m", colors[n - 1]));
+                rest = after;
+            }
+            // Not a placeholder, keep the text as is
+            None => {
+                rendered.push_str("${c");
+                plain.push_str("${c");
+                rest = &tail[3..];
+            }
+        }
+    }
+    rendered.push_str(rest);
+    plain.push_str(rest);
+    *pen = active_sgr(&rendered);
+    (rendered, plain)
+}
+
+/// Collects the SGR escapes (`ESC [ ... m`) after the last reset.
+fn active_sgr(text: &str) -> String {
+    let mut active = String::new();
+    let mut rest = text;
+    while let Some(pos) = rest.find("\x1b[") {
+        let tail = &rest[pos + 2..];
+        let Some(end) = tail.find(|c: char| !c.is_ascii_digit() && c != ';') else {
+            break;
+        };
+        if tail[end..].starts_with('m') {
+            let params = &tail[..end];
+            if params.is_empty() || params == "0" {
+                active.clear();
+            } else {
+                active.push_str(&format!("\x1b[{}m", params));
+            }
+        }
+        rest = &tail[end..];
+    }
+    active
+}
+
+/// Shows one value when GTK and Qt agree, otherwise both tagged by toolkit.
+fn format_toolkit(gtk: &Option<String>, qt: &Option<String>) -> Option<String> {
+    match (gtk, qt) {
+        (Some(gtk), Some(qt)) if gtk == qt => Some(gtk.clone()),
+        (Some(gtk), Some(qt)) => Some(format!("{} [GTK], {} [Qt]", gtk, qt)),
+        (Some(gtk), None) => Some(format!("{} [GTK]", gtk)),
+        (None, Some(qt)) => Some(format!("{} [Qt]", qt)),
+        (None, None) => None,
+    }
 }
 
 impl Display {
     pub fn new(config: Config, info: SystemInfo) -> Self {
-        Self { config, info }
+        Self {
+            config,
+            info,
+            info_width: Cell::new(0),
+            row: Cell::new(0),
+        }
     }
 
-    pub fn render(&self) -> io::Result<()> {
-        execute!(stdout(), Clear(ClearType::All))?;
+    pub fn render(&self, cache: &Cache) -> io::Result<()> {
+        if !self.sequential() {
+            execute!(stdout(), Clear(ClearType::All))?;
+        }
 
         let color = if is_valid_ansi_code(&self.config.color) {
             &self.config.color
@@ -40,75 +181,141 @@ impl Display {
             "38;5;110"
         };
 
-        // Render ASCII art
-        let ascii_lines: Vec<_> = self.config.ascii_art.lines().collect();
-        let max_art_line_len = ascii_lines
-            .iter()
-            .map(|line| {
-                let v = strip_ansi_escapes::strip(line.as_bytes());
-                String::from_utf8(v)
-                    .map(|s| s.chars().count())
-                    .unwrap_or_else(|_| line.chars().count())
-            })
-            .max()
-            .unwrap_or(0);
+        // The logo and the info are placed independently, so every layout
+        // shows the same modules. "right" needs the info's width first.
+        let (logo_rows, info_rows) = match self.config.layout.as_str() {
+            "none" => (0, self.render_info(0, 0, title_color, info_color)?),
+            "top" => {
+                let (_, rows) = self.render_logo(0, color, cache)?;
+                let top = if rows == 0 { 0 } else { rows + 1 };
+                (rows, self.render_info(0, top, title_color, info_color)?)
+            }
+            "right" => {
+                let info_rows = self.render_info(0, 0, title_color, info_color)?;
+                let (_, rows) = self.render_logo(self.info_width.get() + 3, color, cache)?;
+                (rows, info_rows)
+            }
+            _ => {
+                let (columns, rows) = self.render_logo(0, color, cache)?;
+                let x = if columns == 0 { 0 } else { columns + 3 };
+                (rows, self.render_info(x, 0, title_color, info_color)?)
+            }
+        };
 
-        let art_width = max_art_line_len + 3;
-        let mut y = 0;
+        self.move_to(0, logo_rows.max(info_rows) + 1)
+    }
 
-        for line in &ascii_lines {
-            let trimmed_line = line.trim_end();
-            execute!(
-                stdout(),
-                MoveTo(0, y),
-                Print(format!("\x1b[{}m{}\x1b[0m", color, trimmed_line))
-            )?;
-            y += 1;
+    /// The top layout prints line by line below the prompt instead of
+    /// clearing the screen, so it works in MOTDs and scrollback.
+    fn sequential(&self) -> bool {
+        self.config.layout == "top"
+    }
+
+    /// Draws the image logo or the art with its left edge at column `x` and
+    /// returns its size in cells, (0, 0) when there is no logo.
+    fn render_logo(&self, x: usize, color: &str, cache: &Cache) -> io::Result<(usize, u16)> {
+        // An image logo replaces the art when the terminal can draw it.
+        // Other terminals get the image converted to text art if enabled.
+        self.move_to(x, 0)?;
+        let image = self.config.logo_image.as_deref().and_then(|path| {
+            graphics::draw_image(
+                path,
+                self.config.logo_image_width,
+                self.info.terminal.name.as_deref(),
+                self.sequential(),
+            )
+        });
+        match image {
+            Some(placement) => Ok((placement.columns as usize, placement.rows)),
+            None => {
+                let text_art = self.config.logo_image.as_deref().and_then(|path| {
+                    graphics::text_art(
+                        path,
+                        self.config.logo_image_width,
+                        &self.config.logo_image_fallback,
+                        cache,
+                    )
+                });
+                let art = text_art.as_deref().unwrap_or(&self.config.ascii_art);
+                self.render_art(x, art, color)
+            }
         }
+    }
 
-        // Render system info
-        let mut info_y = 0;
+    /// Prints the enabled modules from row `top` on at column `x` and
+    /// returns the first row below them.
+    fn render_info(
+        &self,
+        x: usize,
+        top: u16,
+        title_color: &str,
+        info_color: &str,
+    ) -> io::Result<u16> {
+        let mut info_y = top;
 
         if self.config.show_user {
-            execute!(
-                stdout(),
-                MoveTo(art_width as u16, info_y),
-                Print(format!(
+            self.print_at(
+                x,
+                info_y,
+                &format!(
                     "\x1b[{}m{}@{}\x1b[0m",
                     info_color, self.info.username, self.info.hostname
-                ))
+                ),
             )?;
             info_y += 1;
 
-            execute!(
-                stdout(),
-                MoveTo(art_width as u16, info_y),
-                Print("\x1b[97m-------\x1b[0m")
-            )?;
+            self.print_at(x, info_y, "\x1b[97m-------\x1b[0m")?;
             info_y += 1;
         }
 
         if self.config.show_os {
             let os_info = format!("{} {}", self.info.os_name, self.info.kernel);
-            self.print_line(art_width, info_y, title_color, "OS:", info_color, &os_info)?;
+            self.print_line(x, info_y, title_color, "OS:", info_color, &os_info)?;
             info_y += 1;
         }
 
         if self.config.show_uptime {
-            self.print_line(
-                art_width,
-                info_y,
-                title_color,
-                "Uptime:",
-                info_color,
-                &self.info.uptime,
-            )?;
+            let mut uptime = format_uptime(self.info.uptime_secs, self.config.uptime_long);
+            if self.config.show_boot_time {
+                if let Some(boot) = self
+                    .info
+                    .boot_time
+                    .and_then(|t| format_local_time(t, &self.config.boot_time_format))
+                {
+                    uptime.push_str(&format!(" (since {})", boot));
+                }
+            }
+            self.print_line(x, info_y, title_color, "Uptime:", info_color, &uptime)?;
             info_y += 1;
         }
 
+        if self.config.show_packages && !self.info.packages.is_empty() {
+            let value = self
+                .info
+                .packages
+                .iter()
+                .map(|p| format!("{} ({})", p.count, p.manager))
+                .collect::<Vec<_>>()
+                .join(", ");
+            self.print_line(x, info_y, title_color, "Packages:", info_color, &value)?;
+            info_y += 1;
+        }
+
+        if self.config.show_load {
+            if let Some(ref load) = self.info.load {
+                let [one, five, fifteen] = load.averages;
+                let value = format!(
+                    "{:.2}, {:.2}, {:.2} ({} / {} processes)",
+                    one, five, fifteen, load.running, load.total
+                );
+                self.print_line(x, info_y, title_color, "Load:", info_color, &value)?;
+                info_y += 1;
+            }
+        }
+
         if self.config.show_shell {
             self.print_line(
-                art_width,
+                x,
                 info_y,
                 title_color,
                 "Shell:",
@@ -118,53 +325,157 @@ impl Display {
             info_y += 1;
         }
 
+        if self.config.show_terminal {
+            if let Some(ref terminal) = self.info.terminal.name {
+                self.print_line(x, info_y, title_color, "Terminal:", info_color, terminal)?;
+                info_y += 1;
+            }
+        }
+
+        if self.config.show_terminal_font {
+            if let Some(ref font) = self.info.terminal.font {
+                self.print_line(x, info_y, title_color, "Terminal Font:", info_color, font)?;
+                info_y += 1;
+            }
+        }
+
         if self.config.show_de {
-            self.print_line(
-                art_width,
-                info_y,
-                title_color,
-                "DE:",
-                info_color,
-                &self.info.de,
-            )?;
+            self.print_line(x, info_y, title_color, "DE:", info_color, &self.info.de)?;
             info_y += 1;
         }
 
         if self.config.show_screen {
             if let Some(ref screen) = self.info.screen {
-                self.print_line(
-                    art_width,
-                    info_y,
-                    title_color,
-                    "Screen:",
-                    info_color,
-                    screen,
-                )?;
+                self.print_line(x, info_y, title_color, "Screen:", info_color, screen)?;
+                info_y += 1;
+            }
+        }
+
+        if self.config.show_monitors {
+            for (i, monitor) in self.info.monitors.iter().enumerate() {
+                let label = if i == 0 { "Monitor:" } else { "        " };
+                let value = match monitor.resolution {
+                    Some((width, height)) => format!(
+                        "{} ({}x{}, {})",
+                        monitor.name, width, height, monitor.connector
+                    ),
+                    None => format!("{} ({})", monitor.name, monitor.connector),
+                };
+                self.print_line(x, info_y, title_color, label, info_color, &value)?;
+                info_y += 1;
+            }
+        }
+
+        let theme = &self.info.theme;
+        let toolkit_lines = [
+            (
+                self.config.show_theme,
+                "Theme:",
+                &theme.gtk.theme,
+                &theme.qt.theme,
+            ),
+            (
+                self.config.show_icons,
+                "Icons:",
+                &theme.gtk.icons,
+                &theme.qt.icons,
+            ),
+            (
+                self.config.show_cursor,
+                "Cursor:",
+                &theme.gtk.cursor,
+                &theme.qt.cursor,
+            ),
+            (
+                self.config.show_font,
+                "Font:",
+                &theme.gtk.font,
+                &theme.qt.font,
+            ),
+        ];
+        for (show, label, gtk, qt) in toolkit_lines {
+            if let Some(value) = show.then(|| format_toolkit(gtk, qt)).flatten() {
+                self.print_line(x, info_y, title_color, label, info_color, &value)?;
+                info_y += 1;
+            }
+        }
+
+        if self.config.show_host {
+            if let Some(ref host) = self.info.host {
+                self.print_line(x, info_y, title_color, "Host:", info_color, host)?;
                 info_y += 1;
             }
         }
 
         if self.config.show_motherboard {
             let mobo = self.info.motherboard.as_deref().unwrap_or("Unknown");
+            self.print_line(x, info_y, title_color, "Motherboard:", info_color, mobo)?;
+            info_y += 1;
+        }
+
+        if self.config.show_bios {
+            if let Some(ref bios) = self.info.bios {
+                self.print_line(x, info_y, title_color, "Bios:", info_color, bios)?;
+                info_y += 1;
+            }
+        }
+
+        if self.config.show_virtualization {
+            let virt = &self.info.virt;
+            let value = match (&virt.container, &virt.hypervisor) {
+                (Some(container), Some(hypervisor)) => format!("{} on {}", container, hypervisor),
+                (Some(env), None) | (None, Some(env)) => env.clone(),
+                (None, None) => "None".to_string(),
+            };
             self.print_line(
-                art_width,
+                x,
                 info_y,
                 title_color,
-                "Motherboard:",
+                "Virtualization:",
                 info_color,
-                mobo,
+                &value,
             )?;
             info_y += 1;
         }
 
+        if self.config.show_boot {
+            let boot = &self.info.boot;
+            if let Some(ref init) = boot.init {
+                self.print_line(x, info_y, title_color, "Init:", info_color, init)?;
+                info_y += 1;
+            }
+
+            let mut value = if boot.uefi { "UEFI" } else { "BIOS" }.to_string();
+            if boot.uefi {
+                value.push_str(match boot.secure_boot {
+                    Some(true) => ", Secure Boot enabled",
+                    Some(false) => ", Secure Boot disabled",
+                    None => ", Secure Boot unknown",
+                });
+            }
+            if let Some(ref bootloader) = boot.bootloader {
+                value.push_str(&format!(", {}", bootloader));
+            }
+            self.print_line(x, info_y, title_color, "Boot:", info_color, &value)?;
+            info_y += 1;
+        }
+
+        if self.config.show_security {
+            let value = self.format_security();
+            if !value.is_empty() {
+                self.print_line(x, info_y, title_color, "Security:", info_color, &value)?;
+                info_y += 1;
+            }
+        }
+
         if self.config.show_cpu {
             self.print_line(
-                art_width,
+                x,
                 info_y,
                 title_color,
                 "Cpu:",
                 info_color,
-                &self.info.cpu,
+                &self.format_cpu(),
             )?;
             info_y += 1;
         }
@@ -172,53 +483,101 @@ impl Display {
         if self.config.show_gpu {
             for (i, gpu) in self.info.gpu.iter().enumerate() {
                 let label = if i == 0 { "Gpu:" } else { "    " };
-                self.print_line(art_width, info_y, title_color, label, info_color, gpu)?;
+                let value = match gpu.vram.filter(|_| self.config.show_vram) {
+                    Some(vram) => {
+                        format!(
+                            "{} ({})",
+                            gpu.name,
+                            format_size(vram, self.config.vram_units)
+                        )
+                    }
+                    None => gpu.name.clone(),
+                };
+                self.print_line(x, info_y, title_color, label, info_color, &value)?;
                 info_y += 1;
             }
         }
 
         if self.config.show_ram {
-            let ram_info = format!(
-                "{:.2} / {:.2}gb ({:.0}%)",
-                self.info.ram_used_gb, self.info.ram_total_gb, self.info.ram_percent
-            );
-            self.print_line(
-                art_width,
-                info_y,
-                title_color,
-                "Ram:",
-                info_color,
-                &ram_info,
-            )?;
+            let limit = self
+                .config
+                .cgroup_limits
+                .then(|| self.info.cgroup.effective_memory(self.info.ram_total))
+                .flatten();
+            let ram_info = match limit {
+                Some(limit) => {
+                    let used = self.info.cgroup.memory_usage.unwrap_or(self.info.ram_used);
+                    format!(
+                        "{} ({:.0}%, limit)",
+                        format_size_pair(used, limit, self.config.ram_units),
+                        percent(used, limit)
+                    )
+                }
+                None => format!(
+                    "{} ({:.0}%)",
+                    format_size_pair(
+                        self.info.ram_used,
+                        self.info.ram_total,
+                        self.config.ram_units
+                    ),
+                    percent(self.info.ram_used, self.info.ram_total)
+                ),
+            };
+            self.print_line(x, info_y, title_color, "Ram:", info_color, &ram_info)?;
             info_y += 1;
         }
 
         if self.config.show_swap {
-            let swap_info = format!("{:.2}gb", self.info.swap_total_gb);
-            self.print_line(
-                art_width,
-                info_y,
-                title_color,
-                "Swap:",
-                info_color,
-                &swap_info,
-            )?;
+            let swap_info = format_size(self.info.swap_total, self.config.swap_units);
+            self.print_line(x, info_y, title_color, "Swap:", info_color, &swap_info)?;
             info_y += 1;
         }
 
         if self.config.show_locale {
-            self.print_line(
-                art_width,
-                info_y,
-                title_color,
-                "Locale:",
-                info_color,
-                &self.info.locale,
-            )?;
+            let locale = &self.info.locale;
+            let mut value = locale.main.clone();
+            if !locale.overrides.is_empty() {
+                let overrides: Vec<String> = locale
+                    .overrides
+                    .iter()
+                    .map(|(category, value)| format!("{}={}", category, value))
+                    .collect();
+                value.push_str(&format!(" ({})", overrides.join(", ")));
+            }
+            self.print_line(x, info_y, title_color, "Locale:", info_color, &value)?;
             info_y += 1;
         }
 
+        if self.config.show_timezone {
+            if let Some(ref timezone) = self.info.timezone {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs() as i64)
+                    .unwrap_or(0);
+                let value = match format_local_time(now, "%Z, UTC%z") {
+                    Some(offset) => format!("{} ({})", timezone, offset),
+                    None => timezone.clone(),
+                };
+                self.print_line(x, info_y, title_color, "Timezone:", info_color, &value)?;
+                info_y += 1;
+            }
+        }
+
+        if self.config.show_keyboard {
+            if let Some(ref keyboard) = self.info.keyboard {
+                self.print_line(x, info_y, title_color, "Keyboard:", info_color, keyboard)?;
+                info_y += 1;
+            }
+        }
+
         if self.config.show_disk {
+            let units = self.config.disk_units;
+            let sizes: Vec<String> = self
+                .info
+                .disks
+                .iter()
+                .map(|d| format_size_pair(d.used, d.total, units))
+                .collect();
             let max_len = self
                 .info
                 .disks
@@ -226,34 +585,362 @@ impl Display {
                 .map(|d| d.name.len())
                 .max()
                 .unwrap_or(1);
-            for disk in &self.info.disks {
-                execute!(
-                    stdout(),
-                    MoveTo(art_width as u16, info_y),
-                    Print(format!(
-                        "\x1b[{}mDisk:\x1b[0m \x1b[97m{:<width$}\x1b[0m \x1b[{}m{:>3}gb\x1b[0m \x1b[97m/\x1b[0m \x1b[{}m{:>3}gb ({}%)\x1b[0m",
+            let max_size_len = sizes.iter().map(|s| s.len()).max().unwrap_or(0);
+            for (disk, size) in self.info.disks.iter().zip(&sizes) {
+                self.print_at(
+                    x,
+                    info_y,
+                    &format!(
+                        "\x1b[{}mDisk:\x1b[0m \x1b[97m{:<width$}\x1b[0m \x1b[{}m{:>size_width$} ({:.0}%)\x1b[0m",
                         title_color,
                         disk.name,
                         info_color,
-                        disk.used_gb,
+                        size,
+                        percent(disk.used, disk.total),
+                        width = max_len,
+                        size_width = max_size_len
+                    ),
+                )?;
+                info_y += 1;
+            }
+        }
+
+        if self.config.show_storage {
+            for (i, device) in self.info.storage.iter().enumerate() {
+                let label = if i == 0 { "Storage:" } else { "        " };
+                self.print_line(
+                    x,
+                    info_y,
+                    title_color,
+                    label,
+                    info_color,
+                    &self.format_storage(device),
+                )?;
+                info_y += 1;
+
+                if !device.mounts.is_empty() {
+                    let mounts: Vec<String> = device
+                        .mounts
+                        .iter()
+                        .map(|m| format!("{} ({})", m.target, m.source))
+                        .collect();
+                    self.print_line(
+                        x,
+                        info_y,
+                        title_color,
+                        "        ",
                         info_color,
-                        disk.total_gb,
-                        disk.percent,
-                        width = max_len
-                    ))
+                        &format!("  {}", mounts.join(", ")),
+                    )?;
+                    info_y += 1;
+                }
+            }
+        }
+
+        if self.config.show_battery {
+            let power = &self.info.power;
+            for (i, battery) in power.batteries.iter().enumerate() {
+                let label = if i == 0 { "Battery:" } else { "        " };
+                let mut value = match battery.capacity {
+                    Some(capacity) => format!("{}% ({})", capacity, battery.status),
+                    None => battery.status.clone(),
+                };
+                if power.batteries.len() > 1 {
+                    value = format!("{} {}", battery.name, value);
+                }
+                if let Some(secs) = battery.time_remaining {
+                    let direction = if battery.status == "Charging" {
+                        "until full"
+                    } else {
+                        "left"
+                    };
+                    value.push_str(&format!(", {} {}", format_duration(secs), direction));
+                }
+                if let Some(health) = battery.health {
+                    value.push_str(&format!(", health {:.0}%", health));
+                }
+                if i == 0 {
+                    match power.ac_online {
+                        Some(true) => value.push_str(", AC connected"),
+                        Some(false) => value.push_str(", on battery"),
+                        None => {}
+                    }
+                }
+                self.print_line(x, info_y, title_color, label, info_color, &value)?;
+                info_y += 1;
+            }
+        }
+
+        if self.config.show_audio {
+            let audio = &self.info.audio;
+            if !audio.servers.is_empty() {
+                let mut value = audio.servers.join(", ");
+                if let Some(ref sink) = audio.default_sink {
+                    value.push_str(&format!(" (output: {})", sink));
+                }
+                self.print_line(x, info_y, title_color, "Audio:", info_color, &value)?;
+                info_y += 1;
+                for card in &audio.cards {
+                    self.print_line(x, info_y, title_color, "      ", info_color, card)?;
+                    info_y += 1;
+                }
+            }
+        }
+
+        if self.config.show_media {
+            if let Some(ref media) = self.info.media {
+                let track = match (&media.artist, &media.title) {
+                    (Some(artist), Some(title)) => format!("{} - {}", artist, title),
+                    (None, Some(track)) | (Some(track), None) => track.clone(),
+                    (None, None) => media.player.clone(),
+                };
+                let value = format!("{} ({})", track, media.status);
+                self.print_line(x, info_y, title_color, "Media:", info_color, &value)?;
+                info_y += 1;
+            }
+        }
+
+        if self.config.show_network {
+            let mut first = true;
+            for iface in self
+                .info
+                .network
+                .iter()
+                .filter(|i| self.network_visible(&i.name, i.is_virtual))
+            {
+                let label = if first { "Network:" } else { "        " };
+                first = false;
+                self.print_line(
+                    x,
+                    info_y,
+                    title_color,
+                    label,
+                    info_color,
+                    &self.format_interface(iface),
                 )?;
                 info_y += 1;
             }
         }
 
+        if self.config.show_toolchains {
+            for (i, tool) in self.info.toolchains.iter().enumerate() {
+                let label = if i == 0 { "Toolchains:" } else { "           " };
+                let value = format!("{} {}", tool.name, tool.version);
+                self.print_line(x, info_y, title_color, label, info_color, &value)?;
+                info_y += 1;
+            }
+        }
+
         if self.config.show_color_scheme {
             info_y += 1;
-            self.print_color_scheme(art_width, info_y)?;
-            info_y += 2;
+            info_y = self.print_color_scheme(x, info_y)?;
+        }
+
+        Ok(info_y)
+    }
+
+    /// Prints the art at column `x` and returns its width and the number of
+    /// rows used.
+    fn render_art(&self, x: usize, art: &str, color: &str) -> io::Result<(usize, u16)> {
+        let art_colors: Vec<&str> = (0..ART_COLOR_SLOTS)
+            .map(|i| {
+                self.config
+                    .ascii_colors
+                    .get(i)
+                    .map_or(color, String::as_str)
+            })
+            .collect();
+        let mut pen = format!("\x1b[{}m", art_colors[0]);
+        let ascii_lines: Vec<(String, String)> = art
+            .lines()
+            .map(|line| expand_art_colors(line.trim_end(), &art_colors, &mut pen))
+            .collect();
+        let max_art_line_len = ascii_lines
+            .iter()
+            .map(|(_, plain)| {
+                let v = strip_ansi_escapes::strip(plain.as_bytes());
+                String::from_utf8(v)
+                    .map(|s| s.chars().count())
+                    .unwrap_or_else(|_| plain.chars().count())
+            })
+            .max()
+            .unwrap_or(0);
+
+        // Art without any visible character counts as no logo
+        if max_art_line_len == 0 {
+            return Ok((0, 0));
+        }
+        let mut y = 0;
+
+        for (line, _) in &ascii_lines {
+            self.move_to(x, y)?;
+            execute!(stdout(), Print(format!("{}\x1b[0m", line)))?;
+            y += 1;
+        }
+        Ok((max_art_line_len, y))
+    }
+
+    fn network_visible(&self, name: &str, is_virtual: bool) -> bool {
+        let config = &self.config;
+        if config
+            .network_ignore
+            .iter()
+            .any(|p| matches_pattern(name, p))
+        {
+            return false;
+        }
+        if !config.network_interfaces.is_empty() {
+            return config
+                .network_interfaces
+                .iter()
+                .any(|p| matches_pattern(name, p));
+        }
+        !is_virtual || config.network_show_virtual
+    }
+
+    fn format_interface(&self, iface: &NetworkInterface) -> String {
+        let mut addresses = iface.ipv4.clone();
+        if self.config.show_network_ipv6 {
+            addresses.extend(iface.ipv6.iter().cloned());
         }
+        let mut value = if addresses.is_empty() {
+            format!("{} (no address)", iface.name)
+        } else {
+            format!("{} {}", iface.name, addresses.join(", "))
+        };
+
+        let mut details = Vec::new();
+        if let Some(ref wifi) = iface.wifi {
+            if let Some(ref ssid) = wifi.ssid {
+                details.push(ssid.clone());
+            }
+            if let Some(signal) = wifi.signal_dbm {
+                details.push(format!("{} dBm", signal));
+            }
+        }
+        if let Some(speed) = iface.speed_mbps {
+            details.push(if speed >= 1000 {
+                format!("{} Gb/s", speed as f64 / 1000.0)
+            } else {
+                format!("{} Mb/s", speed)
+            });
+        }
+        if self.config.show_network_mac {
+            if let Some(ref mac) = iface.mac {
+                details.push(mac.clone());
+            }
+        }
+        if !details.is_empty() {
+            value.push_str(&format!(" ({})", details.join(", ")));
+        }
+        value
+    }
+
+    fn format_storage(&self, device: &StorageDevice) -> String {
+        let name = match (&device.vendor, &device.model) {
+            (Some(vendor), Some(model)) if !model.starts_with(vendor.as_str()) => {
+                format!("{} {}", vendor, model)
+            }
+            (_, Some(model)) => model.clone(),
+            (Some(vendor), None) => vendor.clone(),
+            (None, None) => device.name.clone(),
+        };
+
+        let mut details = vec![format_size(device.size, self.config.storage_units)];
+        let kind = match device.rotational {
+            Some(true) => Some("HDD"),
+            Some(false) => Some("SSD"),
+            None => None,
+        };
+        match (&device.transport, kind) {
+            (Some(transport), Some(kind)) => details.push(format!("{} {}", transport, kind)),
+            (Some(transport), None) => details.push(transport.clone()),
+            (None, Some(kind)) => details.push(kind.to_string()),
+            (None, None) => {}
+        }
+        if let Some(ref firmware) = device.firmware {
+            details.push(format!("fw {}", firmware));
+        }
+        format!("{} ({})", name, details.join(", "))
+    }
 
-        execute!(stdout(), MoveTo(0, y.max(info_y) + 1))?;
-        Ok(())
+    fn format_security(&self) -> String {
+        let security = &self.info.security;
+        let config = &self.config;
+        let state = |value: &Option<String>| value.as_deref().unwrap_or("unknown").to_string();
+
+        let mut parts = Vec::new();
+        if config.show_security_selinux {
+            parts.push(format!("SELinux {}", state(&security.selinux)));
+        }
+        if config.show_security_apparmor {
+            parts.push(format!("AppArmor {}", state(&security.apparmor)));
+        }
+        if config.show_security_encryption {
+            parts.push(match security.root_encrypted {
+                Some(true) => "root encrypted".to_string(),
+                Some(false) => "root not encrypted".to_string(),
+                None => "root encryption unknown".to_string(),
+            });
+        }
+        if config.show_security_lockdown {
+            parts.push(format!("lockdown {}", state(&security.lockdown)));
+        }
+        parts.join(", ")
+    }
+
+    fn format_cpu(&self) -> String {
+        let cpu = &self.info.cpu;
+        let mut value = cpu.brand.clone();
+
+        if self.config.show_cpu_sockets {
+            if let Some(sockets) = cpu.sockets.filter(|&s| s > 1) {
+                value = format!("{}x {}", sockets, value);
+            }
+        }
+
+        let mut counts = match (self.config.show_cpu_cores, cpu.cores, cpu.hybrid) {
+            (true, _, Some((p_cores, e_cores))) => {
+                Some(format!("{}P+{}E/{}T", p_cores, e_cores, cpu.threads))
+            }
+            (true, Some(cores), None) => Some(format!("{}C/{}T", cores, cpu.threads)),
+            _ if cpu.threads > 1 => Some(format!("{} threads", cpu.threads)),
+            _ => None,
+        };
+        if self.config.cgroup_limits {
+            if let Some(limit) = self.info.cgroup.effective_cpus(cpu.threads) {
+                let limit = format!("{} CPUs limit", format_cpu_count(limit));
+                counts = Some(match counts {
+                    Some(counts) => format!("{}, {}", counts, limit),
+                    None => limit,
+                });
+            }
+        }
+        if let Some(counts) = counts {
+            value.push_str(&format!(" ({})", counts));
+        }
+
+        if self.config.show_cpu_freq {
+            match (cpu.cur_freq_mhz, cpu.max_freq_mhz) {
+                (Some(cur), Some(max)) => value.push_str(&format!(
+                    " @ {:.2} / {:.2} GHz",
+                    cur as f64 / 1000.0,
+                    max as f64 / 1000.0
+                )),
+                (None, Some(freq)) | (Some(freq), None) => {
+                    value.push_str(&format!(" @ {:.2} GHz", freq as f64 / 1000.0))
+                }
+                (None, None) => {}
+            }
+        }
+
+        if self.config.show_cpu_temp {
+            if let Some(temp) = cpu.temperature {
+                value.push_str(&format!(", {:.0}°C", temp));
+            }
+        }
+
+        value
     }
 
     fn print_line(
@@ -265,42 +952,151 @@ impl Display {
         value_color: &str,
         value: &str,
     ) -> io::Result<()> {
-        execute!(
-            stdout(),
-            MoveTo(x as u16, y),
-            Print(format!(
+        self.print_at(
+            x,
+            y,
+            &format!(
                 "\x1b[{}m{}\x1b[0m \x1b[{}m{}\x1b[0m",
                 title_color, title, value_color, value
-            ))
+            ),
         )
     }
 
-    fn print_color_scheme(&self, x: usize, y: u16) -> io::Result<()> {
+    /// Prints one line of the info and keeps track of the widest one.
+    fn print_at(&self, x: usize, y: u16, text: &str) -> io::Result<()> {
+        let width = strip_ansi_escapes::strip_str(text).chars().count();
+        self.info_width.set(self.info_width.get().max(width));
+        self.move_to(x, y)?;
+        execute!(stdout(), Print(text))
+    }
+
+    /// Moves the cursor to row `y`. The top layout only ever moves down,
+    /// by printing newlines, and always starts lines at column 0.
+    fn move_to(&self, x: usize, y: u16) -> io::Result<()> {
+        if self.sequential() {
+            let lines = y.saturating_sub(self.row.get());
+            self.row.set(self.row.get().max(y));
+            execute!(stdout(), Print("\n".repeat(lines as usize)))
+        } else {
+            execute!(stdout(), MoveTo(x as u16, y))
+        }
+    }
+
+    /// Returns the first row below the blocks.
+    fn print_color_scheme(&self, x: usize, y: u16) -> io::Result<u16> {
         let top_colors = [0, 91, 92, 93, 94, 95, 96, 97];
         let bottom_colors = [30, 31, 32, 33, 34, 35, 36, 37];
+        let palette = self.info.palette.as_ref();
+
+        // With a queried palette the blocks are drawn in truecolor, so they
+        // show what the terminal actually uses for each index
+        let block = |code: u8| {
+            let index = if code >= 90 { code - 90 + 8 } else { code - 30 } as usize;
+            match palette.and_then(|p| p.colors[index]) {
+                Some((r, g, b)) => format!("\x1b[38;2;{};{};{}m███\x1b[0m", r, g, b),
+                None => format!("\x1b[{}m███\x1b[0m", code),
+            }
+        };
 
         let top_line: String = top_colors
             .iter()
-            .map(|&c| {
-                if c == 0 {
-                    "   ".to_string()
-                } else {
-                    format!("\x1b[{}m███\x1b[0m", c)
-                }
-            })
+            .map(|&c| if c == 0 { "   ".to_string() } else { block(c) })
             .collect();
 
-        let bottom_line: String = bottom_colors
-            .iter()
-            .map(|&c| format!("\x1b[{}m███\x1b[0m", c))
-            .collect();
+        let bottom_line: String = bottom_colors.iter().map(|&c| block(c)).collect();
 
-        execute!(
-            stdout(),
-            MoveTo(x as u16, y),
-            Print(&top_line),
-            MoveTo(x as u16, y + 1),
-            Print(&bottom_line)
-        )
+        self.print_at(x, y, &top_line)?;
+        self.print_at(x, y + 1, &bottom_line)?;
+
+        let hex = |color: Option<(u8, u8, u8)>| {
+            color.map(|(r, g, b)| format!("#{:02x}{:02x}{:02x}", r, g, b))
+        };
+        let mut parts = Vec::new();
+        if let Some(fg) = hex(palette.and_then(|p| p.foreground)) {
+            parts.push(format!("fg {}", fg));
+        }
+        if let Some(bg) = hex(palette.and_then(|p| p.background)) {
+            parts.push(format!("bg {}", bg));
+        }
+        if parts.is_empty() {
+            return Ok(y + 2);
+        }
+        self.print_at(x, y + 2, &parts.join("  "))?;
+        Ok(y + 3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_uptime() {
+        assert_eq!(format_uptime(59, false), "0h 0m");
+        assert_eq!(
+            format_uptime(3 * 86400 + 4 * 3600 + 5 * 60, false),
+            "3d 4h 5m"
+        );
+        assert_eq!(format_uptime(60, true), "1 minute");
+        assert_eq!(format_uptime(86400 + 2 * 60, true), "1 day, 2 minutes");
+        assert_eq!(
+            format_uptime(2 * 86400 + 3600 + 60, true),
+            "2 days, 1 hour, 1 minute"
+        );
+    }
+
+    #[test]
+    fn merges_toolkit_values() {
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            format_toolkit(&some("Breeze"), &some("Breeze")).as_deref(),
+            Some("Breeze")
+        );
+        assert_eq!(
+            format_toolkit(&some("Adwaita"), &some("Breeze")).as_deref(),
+            Some("Adwaita [GTK], Breeze [Qt]")
+        );
+        assert_eq!(
+            format_toolkit(&None, &some("Breeze")).as_deref(),
+            Some("Breeze [Qt]")
+        );
+        assert_eq!(format_toolkit(&None, &None), None);
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(59 * 60), "59m");
+        assert_eq!(format_duration(125 * 60 + 30), "2h 5m");
+        assert_eq!(format_cpu_count(2.0), "2");
+        assert_eq!(format_cpu_count(1.5), "1.5");
+    }
+
+    #[test]
+    fn expands_art_color_placeholders() {
+        let colors = ["31", "32"];
+        let mut pen = String::new();
+        let (rendered, plain) = expand_art_colors("${c1}ab${c2}c", &colors, &mut pen);
+        assert_eq!(rendered, "\x1b[0m\x1b[31mab\x1b[0m\x1b[32mc");
+        assert_eq!(plain, "abc");
+        // The color carries over to the next line
+        assert_eq!(pen, "\x1b[32m");
+        let (rendered, plain) = expand_art_colors("d", &colors, &mut pen);
+        assert_eq!(rendered, "\x1b[32md");
+        assert_eq!(plain, "d");
+
+        // Unknown slots are not placeholders
+        let mut pen = String::new();
+        let (rendered, plain) = expand_art_colors("${c9}x ${cz}", &colors, &mut pen);
+        assert_eq!(rendered, "${c9}x ${cz}");
+        assert_eq!(plain, "${c9}x ${cz}");
+        assert_eq!(pen, "");
+    }
+
+    #[test]
+    fn tracks_active_sgr() {
+        assert_eq!(active_sgr("\x1b[1m\x1b[31mx\x1b[0m\x1b[32my"), "\x1b[32m");
+        assert_eq!(active_sgr("\x1b[1;31mx\x1b[K"), "\x1b[1;31m");
+        assert_eq!(active_sgr("\x1b[31mx\x1b[m"), "");
+        assert_eq!(active_sgr("plain"), "");
     }
 }
@@ -0,0 +1,433 @@
+// Image logos over terminal graphics protocols
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use crate::art::expand_path;
+use crate::cache::{Cache, Hint};
+use crate::terminal::supports_sixel;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use std::fs;
+use std::io::{self, Cursor, IsTerminal, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Used when the terminal does not report its size in pixels
+const FALLBACK_CELL: (u32, u32) = (8, 16);
+
+/// The kitty protocol limits each escape sequence to 4096 bytes of payload
+const KITTY_CHUNK: usize = 4096;
+
+/// Conversions are also invalidated when the image or the settings change
+const TEXT_ART_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Bit of each dot in a braille cell, by row and column
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Protocol {
+    Kitty,
+    Iterm,
+    Sixel,
+}
+
+/// Size of the drawn image in terminal cells.
+pub struct Placement {
+    pub columns: u16,
+    pub rows: u16,
+}
+
+/// Draws the image at the cursor, `columns` cells wide. Returns `None`
+/// without printing anything when stdout is not a terminal, the terminal
+/// supports no graphics protocol or the image cannot be read, so the
+/// caller can fall back to the ASCII art.
+///
+/// With `reserve_rows` the rows the image covers are scrolled into view
+/// first and the cursor is put back at its top-left corner afterwards, so
+/// the caller can keep printing without absolute positions.
+pub fn draw_image(
+    path: &str,
+    columns: u16,
+    terminal: Option<&str>,
+    reserve_rows: bool,
+) -> Option<Placement> {
+    if columns == 0 || !io::stdout().is_terminal() {
+        return None;
+    }
+    // Load first, a missing image should not cost a terminal round trip
+    let (bytes, format, image) = load(&expand_path(path)?)?;
+    let protocol = detect_protocol(terminal)?;
+
+    let (cell_width, cell_height) = cell_size();
+    let width_px = u32::from(columns) * cell_width;
+    let height_px = width_px * image.height() / image.width();
+    let rows = height_px.div_ceil(cell_height).max(1) as u16;
+
+    let sequence = match protocol {
+        Protocol::Kitty => {
+            // kitty only takes PNG data, anything else is re-encoded
+            let png = if format == ImageFormat::Png {
+                bytes
+            } else {
+                encode_png(&image)?
+            };
+            kitty(&png, columns, rows)
+        }
+        Protocol::Iterm => iterm(&bytes, columns, rows),
+        Protocol::Sixel => {
+            let resized = image.resize(width_px, rows as u32 * cell_height, FilterType::Triangle);
+            sixel(&resized.to_rgba8())
+        }
+    };
+
+    // Protocols leave the cursor in different places, saving it restores
+    // the same spot for all of them
+    let sequence = if reserve_rows {
+        format!(
+            "{}\x1b[{}A\x1b7{}\x1b8",
+            "\n".repeat(rows as usize),
+            rows,
+            sequence
+        )
+    } else {
+        sequence
+    };
+
+    let mut stdout = io::stdout();
+    stdout.write_all(sequence.as_bytes()).ok()?;
+    stdout.flush().ok()?;
+    Some(Placement { columns, rows })
+}
+
+/// Converts the image to truecolor art `columns` cells wide for terminals
+/// without a graphics protocol. `style` is "halfblock" or "braille",
+/// anything else leaves the ASCII art in place.
+pub fn text_art(path: &str, columns: u16, style: &str, cache: &Cache) -> Option<String> {
+    if !matches!(style, "halfblock" | "braille") || columns == 0 {
+        return None;
+    }
+    let path = expand_path(path)?;
+    let (cell_width, cell_height) = cell_size();
+    let path_str = path.to_string_lossy();
+    let settings = format!("{}:{}:{}x{}", style, columns, cell_width, cell_height);
+    let hints = [
+        Hint::Value(&path_str),
+        Hint::Mtime(&path),
+        Hint::Value(&settings),
+    ];
+
+    // A failed conversion is not stored, so a fixed image shows up at once
+    cache.get_opt("logo-image", TEXT_ART_TTL, &hints, || {
+        let (_, _, image) = load(&path)?;
+        let columns = u32::from(columns);
+        // Cells are not square, keep the aspect ratio in cells
+        let rows = (columns * cell_width * image.height())
+            .div_ceil(image.width() * cell_height)
+            .max(1);
+        let art = if style == "braille" {
+            let resized = image.resize_exact(columns * 2, rows * 4, FilterType::Triangle);
+            braille(&resized.to_rgba8())
+        } else {
+            let resized = image.resize_exact(columns, rows * 2, FilterType::Triangle);
+            half_blocks(&resized.to_rgba8())
+        };
+        Some(art)
+    })
+}
+
+fn load(path: &Path) -> Option<(Vec<u8>, ImageFormat, DynamicImage)> {
+    let bytes = fs::read(path).ok()?;
+    let format = image::guess_format(&bytes).ok()?;
+    let image = image::load_from_memory_with_format(&bytes, format).ok()?;
+    if image.width() == 0 || image.height() == 0 {
+        return None;
+    }
+    Some((bytes, format, image))
+}
+
+fn detect_protocol(terminal: Option<&str>) -> Option<Protocol> {
+    // Multiplexers need passthrough wrapping that is not supported
+    if std::env::var_os("TMUX").is_some() || std::env::var("TERM").is_ok_and(|t| t == "screen") {
+        return None;
+    }
+    let term = std::env::var("TERM").unwrap_or_default();
+    let term_program = std::env::var("TERM_PROGRAM").unwrap_or_default();
+
+    if std::env::var_os("KITTY_WINDOW_ID").is_some()
+        || term == "xterm-kitty"
+        || term == "xterm-ghostty"
+        || matches!(terminal, Some("kitty" | "ghostty"))
+    {
+        Some(Protocol::Kitty)
+    } else if matches!(term_program.as_str(), "iTerm.app" | "WezTerm")
+        || matches!(terminal, Some("iterm.app" | "wezterm"))
+    {
+        Some(Protocol::Iterm)
+    } else if supports_sixel() {
+        Some(Protocol::Sixel)
+    } else {
+        None
+    }
+}
+
+/// Pixel size of one cell from the terminal's reported window size.
+fn cell_size() -> (u32, u32) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            u32::from(size.width / size.columns).max(1),
+            u32::from(size.height / size.rows).max(1),
+        ),
+        _ => FALLBACK_CELL,
+    }
+}
+
+fn encode_png(image: &DynamicImage) -> Option<Vec<u8>> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .ok()?;
+    Some(png)
+}
+
+/// Transmits and displays the PNG in one go, scaled by the terminal to the
+/// given cells. `q=2` suppresses replies and `C=1` keeps the cursor in
+/// place.
+fn kitty(png: &[u8], columns: u16, rows: u16) -> String {
+    let data = STANDARD.encode(png);
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK).collect();
+    let mut sequence = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            sequence.push_str(&format!(
+                "\x1b_Ga=T,f=100,q=2,C=1,c={},r={},m={};{}\x1b\\",
+                columns, rows, more, chunk
+            ));
+        } else {
+            sequence.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    sequence
+}
+
+/// iTerm2 inline images accept the original file in any common format.
+fn iterm(bytes: &[u8], columns: u16, rows: u16) -> String {
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+        bytes.len(),
+        columns,
+        rows,
+        STANDARD.encode(bytes)
+    )
+}
+
+/// Encodes the image with a fixed 6x6x6 color cube. Pixels that are mostly
+/// transparent are left unset so the background shows through.
+fn sixel(image: &RgbaImage) -> String {
+    let (width, height) = image.dimensions();
+    let level = |c: u8| (u32::from(c) * 5 + 127) / 255;
+
+    let mut sequence = format!("\x1bP0;1;0q\"1;1;{};{}", width, height);
+    for index in 0..216u32 {
+        let (r, g, b) = (index / 36, index / 6 % 6, index % 6);
+        sequence.push_str(&format!("#{};2;{};{};{}", index, r * 20, g * 20, b * 20));
+    }
+
+    for band in (0..height).step_by(6) {
+        // One row of sixels per color used in this band
+        let mut rows: Vec<Option<Vec<u8>>> = vec![None; 216];
+        for dy in 0..6.min(height - band) {
+            for x in 0..width {
+                let pixel = image.get_pixel(x, band + dy).0;
+                if pixel[3] < 128 {
+                    continue;
+                }
+                let index = level(pixel[0]) * 36 + level(pixel[1]) * 6 + level(pixel[2]);
+                let row = rows[index as usize].get_or_insert_with(|| vec![0; width as usize]);
+                row[x as usize] |= 1 << dy;
+            }
+        }
+
+        let mut first = true;
+        for (index, row) in rows.iter().enumerate() {
+            let Some(row) = row else {
+                continue;
+            };
+            if !first {
+                // Back to the start of the band for the next color
+                sequence.push('$');
+            }
+            first = false;
+            sequence.push_str(&format!("#{}", index));
+            push_run_length(&mut sequence, row);
+        }
+        sequence.push('-');
+    }
+    sequence.push_str("\x1b\\");
+    sequence
+}
+
+/// Appends the sixels, compressing runs with `!<count><sixel>`.
+fn push_run_length(sequence: &mut String, row: &[u8]) {
+    let mut x = 0;
+    while x < row.len() {
+        let bits = row[x];
+        let run = row[x..].iter().take_while(|&&b| b == bits).count();
+        let sixel = char::from(63 + bits);
+        if run > 3 {
+            sequence.push_str(&format!("!{}{}", run, sixel));
+        } else {
+            for _ in 0..run {
+                sequence.push(sixel);
+            }
+        }
+        x += run;
+    }
+}
+
+/// Two pixels per cell: the upper one as the foreground of `▀`, the lower
+/// one as its background. Transparent pixels show the terminal background.
+fn half_blocks(image: &RgbaImage) -> String {
+    let (width, height) = image.dimensions();
+    let mut art = String::new();
+    for y in (0..height).step_by(2) {
+        for x in 0..width {
+            let top = opaque(image.get_pixel(x, y).0);
+            let bottom = (y + 1 < height)
+                .then(|| opaque(image.get_pixel(x, y + 1).0))
+                .flatten();
+            match (top, bottom) {
+                (Some(top), Some(bottom)) => art.push_str(&format!(
+                    "\x1b[0;{};{}m\u{2580}",
+                    truecolor(38, top),
+                    truecolor(48, bottom)
+                )),
+                (Some(top), None) => {
+                    art.push_str(&format!("\x1b[0;{}m\u{2580}", truecolor(38, top)))
+                }
+                (None, Some(bottom)) => {
+                    art.push_str(&format!("\x1b[0;{}m\u{2584}", truecolor(38, bottom)))
+                }
+                (None, None) => art.push_str("\x1b[0m "),
+            }
+        }
+        art.push_str("\x1b[0m\n");
+    }
+    art
+}
+
+/// 2x4 dots per cell in the average color of the dots that are set. Images
+/// with transparency set the dots of their opaque pixels, opaque images
+/// those brighter than the image's average.
+fn braille(image: &RgbaImage) -> String {
+    let (width, height) = image.dimensions();
+    let luminance =
+        |p: [u8; 4]| (299 * u32::from(p[0]) + 587 * u32::from(p[1]) + 114 * u32::from(p[2])) / 1000;
+    let transparent = image.pixels().any(|p| p.0[3] < 128);
+    let mean = if transparent {
+        0
+    } else {
+        let total: u64 = image.pixels().map(|p| u64::from(luminance(p.0))).sum();
+        (total / u64::from(width * height)) as u32
+    };
+
+    let mut art = String::new();
+    for cell_y in (0..height).step_by(4) {
+        for cell_x in (0..width).step_by(2) {
+            let mut bits = 0;
+            let mut sum = [0u32; 3];
+            let mut count = 0;
+            for (dy, row) in BRAILLE_DOTS.iter().enumerate() {
+                for (dx, bit) in row.iter().enumerate() {
+                    let (x, y) = (cell_x + dx as u32, cell_y + dy as u32);
+                    if x >= width || y >= height {
+                        continue;
+                    }
+                    let pixel = image.get_pixel(x, y).0;
+                    if pixel[3] < 128 || (!transparent && luminance(pixel) <= mean) {
+                        continue;
+                    }
+                    bits |= bit;
+                    for (total, channel) in sum.iter_mut().zip(pixel) {
+                        *total += u32::from(channel);
+                    }
+                    count += 1;
+                }
+            }
+            match char::from_u32(0x2800 + bits).filter(|_| count > 0) {
+                Some(dots) => {
+                    let color = sum.map(|total| (total / count) as u8);
+                    art.push_str(&format!(
+                        "\x1b[0;{}m{}",
+                        truecolor(38, [color[0], color[1], color[2], 255]),
+                        dots
+                    ));
+                }
+                None => art.push_str("\x1b[0m "),
+            }
+        }
+        art.push_str("\x1b[0m\n");
+    }
+    art
+}
+
+/// The pixel's color unless it is mostly transparent.
+fn opaque(pixel: [u8; 4]) -> Option<[u8; 4]> {
+    (pixel[3] >= 128).then_some(pixel)
+}
+
+/// SGR parameters for a 24-bit foreground (38) or background (48) color.
+fn truecolor(layer: u8, pixel: [u8; 4]) -> String {
+    format!("{};2;{};{};{}", layer, pixel[0], pixel[1], pixel[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const GREEN: Rgba<u8> = Rgba([0, 255, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+    const CLEAR: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+    #[test]
+    fn draws_half_blocks() {
+        let pixels = [[RED, CLEAR], [BLUE, GREEN], [GREEN, CLEAR]];
+        let image = RgbaImage::from_fn(2, 3, |x, y| pixels[y as usize][x as usize]);
+        assert_eq!(
+            half_blocks(&image),
+            concat!(
+                "\x1b[0;38;2;255;0;0;48;2;0;0;255m\u{2580}",
+                "\x1b[0;38;2;0;255;0m\u{2584}\x1b[0m\n",
+                // The odd last row has no lower pixel
+                "\x1b[0;38;2;0;255;0m\u{2580}\x1b[0m \x1b[0m\n",
+            )
+        );
+    }
+
+    #[test]
+    fn draws_braille_from_opaque_pixels() {
+        let image = RgbaImage::from_fn(4, 4, |x, y| match (x, y) {
+            (0, 0) => Rgba([200, 0, 0, 255]),
+            (1, 3) => Rgba([0, 100, 0, 255]),
+            _ => CLEAR,
+        });
+        // Dots 1 and 8, in the average color of the two pixels
+        assert_eq!(
+            braille(&image),
+            "\x1b[0;38;2;100;50;0m\u{2881}\x1b[0m \x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn draws_braille_from_bright_pixels() {
+        let white = Rgba([255, 255, 255, 255]);
+        let black = Rgba([0, 0, 0, 255]);
+        let image = RgbaImage::from_fn(2, 4, |x, _| if x == 0 { white } else { black });
+        // The left column is brighter than the average
+        assert_eq!(braille(&image), "\x1b[0;38;2;255;255;255m\u{2847}\x1b[0m\n");
+    }
+}
@@ -0,0 +1,225 @@
+// Locale, timezone and keyboard layout
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use serde::Serialize;
+use std::env;
+use std::fs;
+
+/// Locale categories in the order `locale(1)` prints them
+const CATEGORIES: [&str; 12] = [
+    "LC_CTYPE",
+    "LC_NUMERIC",
+    "LC_TIME",
+    "LC_COLLATE",
+    "LC_MONETARY",
+    "LC_MESSAGES",
+    "LC_PAPER",
+    "LC_NAME",
+    "LC_ADDRESS",
+    "LC_TELEPHONE",
+    "LC_MEASUREMENT",
+    "LC_IDENTIFICATION",
+];
+
+#[derive(Serialize)]
+pub struct LocaleInfo {
+    /// The locale most categories resolve to
+    pub main: String,
+    /// Categories that resolve to something other than `main`
+    pub overrides: Vec<(String, String)>,
+}
+
+pub fn get_locale() -> LocaleInfo {
+    let lookup = |name: &str| env::var(name).ok().filter(|v| !v.is_empty());
+    let mut vars: Vec<(String, String)> = ["LC_ALL", "LANG"]
+        .iter()
+        .chain(CATEGORIES.iter())
+        .filter_map(|name| lookup(name).map(|v| (name.to_string(), v)))
+        .collect();
+
+    // Without LANG or LC_ALL, fill the gaps from the system-wide defaults
+    if !vars.iter().any(|(key, _)| key == "LANG" || key == "LC_ALL") {
+        for (key, value) in read_system_locale() {
+            if !vars.iter().any(|(k, _)| *k == key) {
+                vars.push((key, value));
+            }
+        }
+    }
+
+    resolve(&vars)
+}
+
+/// Applies POSIX precedence: LC_ALL, then LC_<category>, then LANG, then "C".
+fn resolve(vars: &[(String, String)]) -> LocaleInfo {
+    let get = |name: &str| {
+        vars.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    };
+
+    if let Some(all) = get("LC_ALL") {
+        return LocaleInfo {
+            main: all,
+            overrides: Vec::new(),
+        };
+    }
+
+    let main = get("LANG").unwrap_or_else(|| "C".to_string());
+    let overrides = CATEGORIES
+        .iter()
+        .filter_map(|category| {
+            get(category)
+                .filter(|value| *value != main)
+                .map(|value| (category.to_string(), value))
+        })
+        .collect();
+
+    LocaleInfo { main, overrides }
+}
+
+fn read_system_locale() -> Vec<(String, String)> {
+    ["/etc/locale.conf", "/etc/default/locale"]
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| {
+                    let (key, value) = line.trim().split_once('=')?;
+                    let value = value.trim_matches('"');
+                    (!key.starts_with('#') && !value.is_empty())
+                        .then(|| (key.to_string(), value.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the IANA name of the system timezone, e.g. `Europe/Berlin`.
+pub fn get_timezone() -> Option<String> {
+    if let Some(tz) = env::var("TZ").ok().filter(|tz| !tz.is_empty()) {
+        return Some(tz.trim_start_matches(':').to_string());
+    }
+
+    if let Ok(target) = fs::read_link("/etc/localtime") {
+        let target = target.to_string_lossy();
+        if let Some((_, zone)) = target.split_once("zoneinfo/") {
+            return Some(zone.to_string());
+        }
+    }
+
+    fs::read_to_string("/etc/timezone")
+        .ok()
+        .map(|tz| tz.trim().to_string())
+        .filter(|tz| !tz.is_empty())
+}
+
+/// Returns the keyboard layout, e.g. `us,de (intl)`.
+pub fn get_keyboard_layout() -> Option<String> {
+    // Wayland compositors take the layout from the session environment
+    if let Some(layout) = env::var("XKB_DEFAULT_LAYOUT")
+        .ok()
+        .filter(|l| !l.is_empty())
+    {
+        return Some(with_variant(layout, env::var("XKB_DEFAULT_VARIANT").ok()));
+    }
+
+    // Written by `localectl set-x11-keymap`
+    if let Ok(content) = fs::read_to_string("/etc/X11/xorg.conf.d/00-keyboard.conf") {
+        let option = |name: &str| {
+            content.lines().find_map(|line| {
+                let line = line.trim();
+                let rest = line.strip_prefix("Option")?.trim();
+                let rest = rest.strip_prefix(&format!("\"{}\"", name))?;
+                Some(rest.trim().trim_matches('"').to_string())
+            })
+        };
+        if let Some(layout) = option("XkbLayout") {
+            return Some(with_variant(layout, option("XkbVariant")));
+        }
+    }
+
+    // Debian and derivatives
+    if let Ok(content) = fs::read_to_string("/etc/default/keyboard") {
+        let var = |name: &str| read_shell_var(&content, name);
+        if let Some(layout) = var("XKBLAYOUT") {
+            return Some(with_variant(layout, var("XKBVARIANT")));
+        }
+    }
+
+    // Console keymap as the last resort
+    fs::read_to_string("/etc/vconsole.conf")
+        .ok()
+        .and_then(|content| read_shell_var(&content, "KEYMAP"))
+}
+
+fn read_shell_var(content: &str, name: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let (key, value) = line.trim().split_once('=')?;
+        let value = value.trim().trim_matches('"');
+        (key == name && !value.is_empty()).then(|| value.to_string())
+    })
+}
+
+fn with_variant(layout: String, variant: Option<String>) -> String {
+    match variant.filter(|v| !v.trim_matches(',').is_empty()) {
+        Some(variant) => format!("{} ({})", layout, variant),
+        None => layout,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn lc_all_wins_over_everything() {
+        let info = resolve(&vars(&[
+            ("LC_ALL", "C.UTF-8"),
+            ("LANG", "de_DE.UTF-8"),
+            ("LC_TIME", "en_GB.UTF-8"),
+        ]));
+        assert_eq!(info.main, "C.UTF-8");
+        assert!(info.overrides.is_empty());
+    }
+
+    #[test]
+    fn categories_override_lang() {
+        let info = resolve(&vars(&[
+            ("LANG", "en_US.UTF-8"),
+            ("LC_TIME", "de_DE.UTF-8"),
+            ("LC_CTYPE", "en_US.UTF-8"),
+        ]));
+        assert_eq!(info.main, "en_US.UTF-8");
+        assert_eq!(
+            info.overrides,
+            [("LC_TIME".to_string(), "de_DE.UTF-8".to_string())]
+        );
+        assert_eq!(resolve(&[]).main, "C");
+    }
+
+    #[test]
+    fn reads_shell_variables() {
+        let content = "# comment\nXKBLAYOUT=\"us,de\"\nXKBVARIANT=\",\"\nKEYMAP=\n";
+        assert_eq!(
+            read_shell_var(content, "XKBLAYOUT").as_deref(),
+            Some("us,de")
+        );
+        assert_eq!(read_shell_var(content, "KEYMAP"), None);
+        assert_eq!(
+            with_variant("us,de".to_string(), read_shell_var(content, "XKBVARIANT")),
+            "us,de"
+        );
+        assert_eq!(
+            with_variant("us".to_string(), Some("intl".to_string())),
+            "us (intl)"
+        );
+    }
+}
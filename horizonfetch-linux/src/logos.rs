@@ -0,0 +1,333 @@
+// Built-in distribution logos
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use std::fs;
+
+pub struct Logo {
+    pub name: &'static str,
+    /// os-release `ID` values, also matched against `ID_LIKE`
+    pub ids: &'static [&'static str],
+    pub color: &'static str,
+    pub art: &'static str,
+}
+
+/// Shown when the distribution has no logo of its own
+pub const FALLBACK: Logo = Logo {
+    name: "linux",
+    ids: &["linux"],
+    color: "34",
+    art: r#"
+       ___
+      (.. \
+      (<> |
+     / __  \
+    ( /  \ /|
+   _/\ __)/_)
+   \/-____\/
+"#,
+};
+
+pub static LOGOS: [Logo; 17] = [
+    Logo {
+        name: "alpine",
+        ids: &["alpine"],
+        color: "34",
+        art: r#"
+      /\ /\
+     /  \  \
+    /    \  \
+   /      \  \
+  /        \  \
+ /__________\__\
+"#,
+    },
+    Logo {
+        name: "arch",
+        ids: &["arch", "archarm"],
+        color: "36",
+        art: r#"
+       /\
+      /  \
+     /\   \
+    /      \
+   /   ,,   \
+  /   |  |  -\
+ /_-''    ''-_\
+"#,
+    },
+    Logo {
+        name: "debian",
+        ids: &["debian", "raspbian"],
+        color: "31",
+        art: r#"
+    _____
+   /  __ \
+  |  /    |
+  |  \___-
+  -_
+    --_
+"#,
+    },
+    Logo {
+        name: "elementary",
+        ids: &["elementary"],
+        color: "37",
+        art: r#"
+    _______
+   / ____  \
+  /  |  /  /\
+  |__\ /  / |
+  \   /__/  /
+   \_______/
+"#,
+    },
+    Logo {
+        name: "endeavouros",
+        ids: &["endeavouros"],
+        color: "35",
+        art: r#"
+        /o.
+      /sssso-
+    /ossssssso:
+   /ssssssssssso+
+  /ssssssssssssssso+
+  //osssssssssssso+-
+    `+++++++++++++-`
+"#,
+    },
+    Logo {
+        name: "fedora",
+        ids: &["fedora"],
+        color: "34",
+        art: r#"
+        ,'''''.
+       |   ,.  |
+       |  |  '_'
+  ,....|  |..
+.'  ,_;|   ..'
+|  |   |  |
+|  ',_,'  |
+ '.     ,'
+   '''''
+"#,
+    },
+    Logo {
+        name: "gentoo",
+        ids: &["gentoo"],
+        color: "35",
+        art: r#"
+   _-----_
+  (       \
+  \    0   \
+   \        )
+   /      _/
+  (     _-
+  \____-
+"#,
+    },
+    Logo {
+        name: "manjaro",
+        ids: &["manjaro", "manjaro-arm"],
+        color: "32",
+        art: r#"
+  ||||||||| ||||
+  ||||||||| ||||
+  ||||      ||||
+  |||| |||| ||||
+  |||| |||| ||||
+  |||| |||| ||||
+  |||| |||| ||||
+"#,
+    },
+    Logo {
+        name: "mint",
+        ids: &["linuxmint"],
+        color: "32",
+        art: r#"
+   ___________
+  |_          \
+    | | _____ |
+    | | | | | |
+    | | | | | |
+    | \_____/ |
+    \_________/
+"#,
+    },
+    Logo {
+        name: "nixos",
+        ids: &["nixos"],
+        color: "34",
+        art: r#"
+    \\  \\ //
+   ==\\__\\/ //
+     //   \\//
+  ==//     //==
+   //\\___//
+  // /\\  \\==
+    // \\  \\
+"#,
+    },
+    Logo {
+        name: "opensuse",
+        ids: &[
+            "opensuse",
+            "opensuse-tumbleweed",
+            "opensuse-leap",
+            "opensuse-slowroll",
+            "suse",
+            "sles",
+        ],
+        color: "32",
+        art: r#"
+    _______
+  __|   __ \
+       / .\ \
+       \__/ |
+     _______|
+     \_______
+  __________/
+"#,
+    },
+    Logo {
+        name: "pop",
+        ids: &["pop"],
+        color: "36",
+        art: r#"
+  ______
+  \   _ \        __
+   \ \ \ \      / /
+    \ \_\ \    / /
+     \  ___\  /_/
+      \ \    _
+     __\_\__(_)_
+    (___________)
+"#,
+    },
+    Logo {
+        name: "rhel",
+        ids: &["rhel", "centos", "rocky", "almalinux"],
+        color: "31",
+        art: r#"
+       .-----.
+    .-'  ___  '-.
+   /   .'   '.   \
+  |____|_____|____|
+   \             /
+    '-.._____..-'
+"#,
+    },
+    Logo {
+        name: "slackware",
+        ids: &["slackware"],
+        color: "34",
+        art: r#"
+     ________
+    /  ______|
+    | |______
+    \______  \
+     ______| |
+  | |________/
+  |____________
+"#,
+    },
+    Logo {
+        name: "ubuntu",
+        ids: &["ubuntu"],
+        color: "38;5;202",
+        art: r#"
+           _
+       ---(_)
+   _/  ---  \
+  (_) |   |
+    \  --- _/
+       ---(_)
+"#,
+    },
+    Logo {
+        name: "void",
+        ids: &["void"],
+        color: "32",
+        art: r#"
+      _______
+   _ \______ -
+  | \  ___  \ |
+  | | /   \ | |
+  | | \___/ | |
+  | \______ \_|
+   -_______\
+"#,
+    },
+    FALLBACK,
+];
+
+/// Resolves the `logo` setting: `none` shows no logo, a known name picks
+/// that logo and anything else selects one from /etc/os-release.
+pub fn select(setting: &str) -> Option<&'static Logo> {
+    match setting {
+        "none" => None,
+        name => Some(find(name).unwrap_or_else(detect)),
+    }
+}
+
+pub fn find(name: &str) -> Option<&'static Logo> {
+    LOGOS.iter().find(|logo| logo.name == name)
+}
+
+/// Matches `ID` first and then every entry of `ID_LIKE`, so derivatives
+/// without a logo of their own get their parent's.
+pub fn detect() -> &'static Logo {
+    let content = fs::read_to_string("/etc/os-release")
+        .or_else(|_| fs::read_to_string("/usr/lib/os-release"))
+        .unwrap_or_default();
+    from_os_release(&content)
+}
+
+fn from_os_release(content: &str) -> &'static Logo {
+    let value = |key: &str| {
+        content
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+            .map(|v| v.trim().trim_matches('"').to_lowercase())
+            .unwrap_or_default()
+    };
+    let id = value("ID");
+    let id_like = value("ID_LIKE");
+
+    std::iter::once(id.as_str())
+        .chain(id_like.split_whitespace())
+        .find_map(|id| LOGOS.iter().find(|logo| logo.ids.contains(&id)))
+        .unwrap_or(&FALLBACK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_logos_by_name() {
+        assert_eq!(find("arch").map(|l| l.name), Some("arch"));
+        assert!(find("windows").is_none());
+        assert!(select("none").is_none());
+        assert_eq!(select("fedora").map(|l| l.name), Some("fedora"));
+    }
+
+    #[test]
+    fn matches_os_release_ids() {
+        assert_eq!(
+            from_os_release("ID=ubuntu\nID_LIKE=debian\n").name,
+            "ubuntu"
+        );
+        // Derivatives fall back to ID_LIKE, in order
+        assert_eq!(
+            from_os_release("ID=\"kali\"\nID_LIKE=\"debian\"\n").name,
+            "debian"
+        );
+        assert_eq!(
+            from_os_release("ID=garuda\nID_LIKE=\"unknown arch\"\n").name,
+            "arch"
+        );
+        assert_eq!(from_os_release("ID=Rocky\n").name, "rhel");
+        assert_eq!(from_os_release("ID=haiku\n").name, "linux");
+        assert_eq!(from_os_release("").name, "linux");
+    }
+}
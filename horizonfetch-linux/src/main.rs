@@ -1,24 +1,80 @@
 // HorizonFetch Linux Edition
 // AnmiTaliDev <anmitalidev@nuros.org>
 
+mod art;
+mod audio;
+mod battery;
+mod boot;
+mod cache;
+mod cgroup;
+mod cli;
 mod config;
+mod cpu;
 mod display;
+mod graphics;
+mod locale;
+mod logos;
+mod media;
+mod monitor;
+mod network;
+mod packages;
+mod privacy;
+mod process;
+mod security;
+mod storage;
+mod sysfs;
 mod system;
+mod terminal;
+#[cfg(test)]
+mod testutil;
+mod theme;
+mod toolchain;
+mod units;
+mod virt;
 
+use cache::Cache;
+use cli::Action;
 use config::Config;
 use display::Display;
+use std::time::Instant;
 use system::SystemInfo;
 
 fn main() -> std::io::Result<()> {
+    let args = match cli::parse() {
+        Action::Run(args) => args,
+        Action::Exit => return Ok(()),
+    };
+
     // Load configuration
     let config = Config::load_default();
 
     // Gather system information
-    let info = SystemInfo::gather();
+    let cache = Cache::new(args.cache);
+    let start = Instant::now();
+    let mut info = SystemInfo::gather(&config, &cache, args.json);
+    let gather_time = start.elapsed();
+
+    // Mask personal data before anything is printed
+    if args.redact || config.privacy.redact {
+        privacy::redact(&mut info, &config.privacy);
+    }
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&info).map_err(std::io::Error::other)?;
+        println!("{}", json);
+    } else {
+        // Display everything
+        let display = Display::new(config, info);
+        display.render(&cache)?;
+    }
 
-    // Display everything
-    let display = Display::new(config, info);
-    display.render()?;
+    // Printed after the output, the side layouts clear the screen first
+    if args.debug {
+        for timing in cache.timings() {
+            eprintln!("{}", timing);
+        }
+        eprintln!("gather: {:.2} ms", gather_time.as_secs_f64() * 1000.0);
+    }
 
     Ok(())
 }
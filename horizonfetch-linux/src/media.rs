@@ -0,0 +1,194 @@
+// Now playing media over MPRIS
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use zbus::blocking::connection::Builder;
+use zbus::blocking::fdo::{DBusProxy, PropertiesProxy};
+use zbus::blocking::Connection;
+use zbus::names::InterfaceName;
+use zbus::zvariant::OwnedValue;
+
+const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+
+/// Upper bound for connecting and for every D-Bus call, so an unresponsive
+/// bus or player cannot stall the whole fetch.
+const CALL_TIMEOUT: Duration = Duration::from_millis(250);
+/// Upper bound for querying all players together. Players left once it has
+/// passed are skipped, so many slow ones cost no more than one extra call.
+const QUERY_DEADLINE: Duration = Duration::from_millis(500);
+
+#[derive(Serialize)]
+pub struct MediaInfo {
+    /// Player name from the bus name, e.g. `spotify` or `firefox.instance_1_42`
+    pub player: String,
+    pub artist: Option<String>,
+    pub title: Option<String>,
+    pub status: String,
+}
+
+/// Returns the currently playing track, falling back to a paused one.
+pub fn get_media() -> Option<MediaInfo> {
+    get_media_from(Builder::session)
+}
+
+/// Like [`get_media`] on the bus `builder` connects to.
+fn get_media_from<F>(builder: F) -> Option<MediaInfo>
+where
+    F: FnOnce() -> zbus::Result<Builder<'static>> + Send + 'static,
+{
+    let connection = connect(builder)?;
+    let names = DBusProxy::new(&connection).ok()?.list_names().ok()?;
+    let deadline = Instant::now() + QUERY_DEADLINE;
+    let mut players: Vec<MediaInfo> = names
+        .iter()
+        .filter_map(|name| name.as_str().strip_prefix(MPRIS_PREFIX).map(|p| (name, p)))
+        .take_while(|_| Instant::now() < deadline)
+        .filter_map(|(name, player)| query_player(&connection, name.as_str(), player))
+        .filter(|info| info.status != "Stopped")
+        .collect();
+
+    players.sort_by_key(|info| info.status != "Playing");
+    players.into_iter().next()
+}
+
+/// Connects in a thread so a bus that accepts the socket but never
+/// finishes the handshake is abandoned after the timeout.
+fn connect<F>(builder: F) -> Option<Connection>
+where
+    F: FnOnce() -> zbus::Result<Builder<'static>> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let connection = builder().and_then(|builder| builder.method_timeout(CALL_TIMEOUT).build());
+        let _ = sender.send(connection);
+    });
+    receiver.recv_timeout(CALL_TIMEOUT).ok()?.ok()
+}
+
+fn query_player(connection: &Connection, bus_name: &str, player: &str) -> Option<MediaInfo> {
+    let proxy = PropertiesProxy::builder(connection)
+        .destination(bus_name)
+        .ok()?
+        .path(MPRIS_PATH)
+        .ok()?
+        .build()
+        .ok()?;
+    let interface = InterfaceName::try_from(PLAYER_INTERFACE).ok()?;
+
+    let status: String = proxy
+        .get(interface.clone(), "PlaybackStatus")
+        .ok()
+        .and_then(|value| String::try_from(value).ok())?;
+    let metadata: HashMap<String, OwnedValue> = proxy
+        .get(interface, "Metadata")
+        .ok()
+        .and_then(|value| HashMap::try_from(value).ok())
+        .unwrap_or_default();
+
+    let artist = metadata
+        .get("xesam:artist")
+        .and_then(|value| Vec::<String>::try_from(value.try_clone().ok()?).ok())
+        .map(|artists| artists.join(", "))
+        .filter(|artist| !artist.is_empty());
+    let title = metadata
+        .get("xesam:title")
+        .and_then(|value| String::try_from(value.try_clone().ok()?).ok())
+        .filter(|title| !title.is_empty());
+
+    Some(MediaInfo {
+        player: player.to_string(),
+        artist,
+        title,
+        status,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::TempDir;
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command, Stdio};
+    use zbus::zvariant::Value;
+
+    struct DummyPlayer;
+
+    #[zbus::interface(name = "org.mpris.MediaPlayer2.Player")]
+    impl DummyPlayer {
+        #[zbus(property)]
+        fn playback_status(&self) -> String {
+            "Playing".to_string()
+        }
+
+        #[zbus(property)]
+        fn metadata(&self) -> HashMap<String, OwnedValue> {
+            let value = |v: Value| OwnedValue::try_from(v).unwrap();
+            HashMap::from([
+                ("xesam:title".to_string(), value(Value::from("Song"))),
+                (
+                    "xesam:artist".to_string(),
+                    value(Value::from(vec!["Artist A", "Artist B"])),
+                ),
+            ])
+        }
+    }
+
+    fn bus(address: &str) -> impl FnOnce() -> zbus::Result<Builder<'static>> + Send + 'static {
+        let address = address.to_string();
+        move || Builder::address(address.as_str())
+    }
+
+    #[test]
+    fn gives_up_on_a_bus_that_never_answers() {
+        let dir = TempDir::new("bus");
+        let socket = dir.join("socket");
+        let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+        let start = Instant::now();
+        assert!(get_media_from(bus(&format!("unix:path={}", socket.display()))).is_none());
+        assert!(start.elapsed() < CALL_TIMEOUT * 4);
+    }
+
+    /// Runs against a private bus: `cargo test -- --ignored`, needs
+    /// `dbus-daemon` in PATH.
+    #[test]
+    #[ignore]
+    fn reads_a_dummy_player_from_a_private_bus() {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address=1"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("dbus-daemon not found");
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap())
+            .read_line(&mut address)
+            .unwrap();
+        let address = address.trim();
+
+        let without_player = get_media_from(bus(address));
+        let service = Builder::address(address)
+            .unwrap()
+            .name("org.mpris.MediaPlayer2.dummy")
+            .unwrap()
+            .serve_at(MPRIS_PATH, DummyPlayer)
+            .unwrap()
+            .build()
+            .unwrap();
+        let with_player = get_media_from(bus(address));
+        drop(service);
+        let _ = daemon.kill();
+        let _ = daemon.wait();
+
+        assert!(without_player.is_none());
+        let media = with_player.expect("dummy player not found");
+        assert_eq!(media.player, "dummy");
+        assert_eq!(media.status, "Playing");
+        assert_eq!(media.artist.as_deref(), Some("Artist A, Artist B"));
+        assert_eq!(media.title.as_deref(), Some("Song"));
+    }
+}
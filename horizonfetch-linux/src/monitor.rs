@@ -0,0 +1,155 @@
+// Connected monitors from their EDID
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const DRM_DIR: &str = "/sys/class/drm";
+
+const EDID_HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+
+#[derive(Serialize, Deserialize)]
+pub struct Monitor {
+    /// DRM connector, e.g. `DP-1` or `eDP-1`
+    pub connector: String,
+    /// Model name, or the PNP vendor id and product code without one
+    pub name: String,
+    /// Preferred (native) mode
+    pub resolution: Option<(u16, u16)>,
+}
+
+/// Connected connectors in sysfs order, used as the cache key: plugging
+/// in or removing a monitor changes it.
+pub fn connected_outputs() -> String {
+    connectors()
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn get_monitors() -> Vec<Monitor> {
+    connectors()
+        .into_iter()
+        .filter_map(|(connector, dir)| {
+            let edid = fs::read(dir.join("edid")).ok()?;
+            let (name, resolution) = parse_edid(&edid)?;
+            Some(Monitor {
+                connector,
+                name,
+                resolution,
+            })
+        })
+        .collect()
+}
+
+/// `card0-DP-1` style entries whose status is `connected`.
+fn connectors() -> Vec<(String, PathBuf)> {
+    let mut connectors: Vec<_> = fs::read_dir(DRM_DIR)
+        .map(|dir| {
+            dir.flatten()
+                .filter_map(|e| {
+                    let name = e.file_name().into_string().ok()?;
+                    let (card, connector) = name.split_once('-')?;
+                    card.strip_prefix("card")?.parse::<u32>().ok()?;
+                    let status = fs::read_to_string(e.path().join("status")).ok()?;
+                    (status.trim() == "connected").then(|| (connector.to_string(), e.path()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    connectors.sort_by(|a, b| a.1.cmp(&b.1));
+    connectors
+}
+
+/// Reads the model name and preferred resolution from an EDID base block.
+fn parse_edid(edid: &[u8]) -> Option<(String, Option<(u16, u16)>)> {
+    if edid.len() < 128 || edid[..8] != EDID_HEADER {
+        return None;
+    }
+
+    let mut name = None;
+    let mut resolution = None;
+    for descriptor in edid[54..126].chunks(18) {
+        if descriptor[0] != 0 || descriptor[1] != 0 {
+            // Detailed timing descriptor, the first one is the preferred mode
+            if resolution.is_none() {
+                let width = descriptor[2] as u16 | ((descriptor[4] as u16 & 0xf0) << 4);
+                let height = descriptor[5] as u16 | ((descriptor[7] as u16 & 0xf0) << 4);
+                resolution = Some((width, height));
+            }
+        } else if descriptor[3] == 0xfc && name.is_none() {
+            name = descriptor_text(&descriptor[5..]);
+        }
+    }
+
+    Some((name.unwrap_or_else(|| product_id(edid)), resolution))
+}
+
+/// Descriptor text ends at a newline and is padded with spaces.
+fn descriptor_text(bytes: &[u8]) -> Option<String> {
+    let text: String = bytes
+        .iter()
+        .take_while(|&&b| b != b'\n')
+        .map(|&b| b as char)
+        .collect();
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Three-letter PNP vendor id and the product code, e.g. `DEL A0C4`.
+fn product_id(edid: &[u8]) -> String {
+    let vendor = u16::from_be_bytes([edid[8], edid[9]]);
+    let letters: String = [10, 5, 0]
+        .iter()
+        .map(|shift| (b'A' - 1 + ((vendor >> shift) & 0x1f) as u8) as char)
+        .collect();
+    format!(
+        "{} {:04X}",
+        letters,
+        u16::from_le_bytes([edid[10], edid[11]])
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A base block with a 2560x1440 preferred mode and the given name.
+    fn edid(name: Option<&str>) -> Vec<u8> {
+        let mut edid = vec![0u8; 128];
+        edid[..8].copy_from_slice(&EDID_HEADER);
+        // "DEL", product code 0xA0C4
+        edid[8..12].copy_from_slice(&[0x10, 0xac, 0xc4, 0xa0]);
+        edid[54..72].copy_from_slice(&[
+            0x56, 0x5e, 0x00, 0xa0, 0xa0, 0xa0, 0x29, 0x50, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ]);
+        if let Some(name) = name {
+            let descriptor = &mut edid[72..90];
+            descriptor[3] = 0xfc;
+            descriptor[5..].fill(b' ');
+            descriptor[5..5 + name.len()].copy_from_slice(name.as_bytes());
+            descriptor[5 + name.len()] = b'\n';
+        }
+        edid
+    }
+
+    #[test]
+    fn parses_name_and_preferred_mode() {
+        assert_eq!(
+            parse_edid(&edid(Some("DELL U2720Q"))),
+            Some(("DELL U2720Q".to_string(), Some((2560, 1440))))
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_product_id() {
+        assert_eq!(
+            parse_edid(&edid(None)),
+            Some(("DEL A0C4".to_string(), Some((2560, 1440))))
+        );
+        assert_eq!(parse_edid(&[0; 128]), None);
+        assert_eq!(parse_edid(&EDID_HEADER), None);
+    }
+}
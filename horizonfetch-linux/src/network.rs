@@ -0,0 +1,179 @@
+// Network interfaces
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use crate::process::run_within;
+use crate::sysfs::read_attr;
+use nix::ifaddrs::getifaddrs;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+const NET_DIR: &str = "/sys/class/net";
+
+/// `iw` answers from the kernel right away, anything longer means it hangs
+const IW_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Serialize)]
+pub struct NetworkInterface {
+    pub name: String,
+    /// Loopback, bridges, veth pairs and other devices without hardware
+    pub is_virtual: bool,
+    pub ipv4: Vec<String>,
+    pub ipv6: Vec<String>,
+    pub mac: Option<String>,
+    pub speed_mbps: Option<u32>,
+    pub wifi: Option<WifiInfo>,
+}
+
+#[derive(Serialize)]
+pub struct WifiInfo {
+    pub ssid: Option<String>,
+    pub signal_dbm: Option<i32>,
+}
+
+pub fn get_interfaces() -> Vec<NetworkInterface> {
+    let mut names: Vec<String> = fs::read_dir(NET_DIR)
+        .map(|dir| {
+            dir.flatten()
+                .filter_map(|e| e.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+
+    let addresses: Vec<_> = getifaddrs().map(|a| a.collect()).unwrap_or_default();
+
+    let mut interfaces = Vec::new();
+    for name in names {
+        let dir = Path::new(NET_DIR).join(&name);
+
+        let mut ipv4 = Vec::new();
+        let mut ipv6 = Vec::new();
+        for addr in addresses.iter().filter(|a| a.interface_name == name) {
+            let Some(address) = addr.address.as_ref() else {
+                continue;
+            };
+            let netmask = addr.netmask.as_ref();
+            if let Some(v4) = address.as_sockaddr_in() {
+                let prefix = netmask
+                    .and_then(|m| m.as_sockaddr_in())
+                    .map(|m| m.ip().to_bits().count_ones());
+                ipv4.push(with_prefix(v4.ip().to_string(), prefix));
+            } else if let Some(v6) = address.as_sockaddr_in6() {
+                // Link-local addresses are the same on every network
+                if v6.ip().segments()[0] & 0xffc0 == 0xfe80 {
+                    continue;
+                }
+                let prefix = netmask
+                    .and_then(|m| m.as_sockaddr_in6())
+                    .map(|m| m.ip().to_bits().count_ones());
+                ipv6.push(with_prefix(v6.ip().to_string(), prefix));
+            }
+        }
+
+        // Some drivers (tun, wireguard) never report "up", only "unknown"
+        let operstate = read_attr(&dir, "operstate").unwrap_or_default();
+        let active =
+            operstate == "up" || (operstate == "unknown" && (!ipv4.is_empty() || !ipv6.is_empty()));
+        if !active {
+            continue;
+        }
+
+        let is_virtual = fs::canonicalize(&dir)
+            .map(|p| p.starts_with("/sys/devices/virtual"))
+            .unwrap_or(false);
+        let is_wireless = dir.join("wireless").exists() || dir.join("phy80211").exists();
+
+        interfaces.push(NetworkInterface {
+            mac: read_attr(&dir, "address").filter(|mac| mac != "00:00:00:00:00:00"),
+            // Unknown link speed is reported as -1 or fails to read
+            speed_mbps: read_attr(&dir, "speed")
+                .and_then(|s| s.parse::<i64>().ok())
+                .filter(|&s| s > 0)
+                .map(|s| s as u32),
+            wifi: is_wireless.then(|| read_wifi(&name)),
+            name,
+            is_virtual,
+            ipv4,
+            ipv6,
+        });
+    }
+
+    interfaces
+}
+
+/// Matches an interface name against a pattern with an optional `*` wildcard,
+/// e.g. `docker*` or `*0`.
+pub fn matches_pattern(name: &str, pattern: &str) -> bool {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            name.len() >= prefix.len() + suffix.len()
+                && name.starts_with(prefix)
+                && name.ends_with(suffix)
+        }
+        None => name == pattern,
+    }
+}
+
+fn with_prefix(address: String, prefix: Option<u32>) -> String {
+    match prefix {
+        Some(prefix) => format!("{}/{}", address, prefix),
+        None => address,
+    }
+}
+
+fn read_wifi(name: &str) -> WifiInfo {
+    // /proc/net/wireless: "wlan0: 0000   58.  -52.  -256 ..."
+    let signal_dbm = fs::read_to_string("/proc/net/wireless")
+        .ok()
+        .and_then(|content| {
+            content.lines().find_map(|line| {
+                let (iface, rest) = line.trim().split_once(':')?;
+                if iface != name {
+                    return None;
+                }
+                let level = rest.split_whitespace().nth(2)?;
+                level.trim_end_matches('.').parse::<f64>().ok()
+            })
+        })
+        .map(|level| level as i32);
+
+    // The SSID is only available over nl80211, which `iw` speaks for us
+    let ssid = run_within(Command::new("iw").args(["dev", name, "link"]), IW_TIMEOUT)
+        .and_then(|output| {
+            output
+                .stdout
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("SSID:"))
+                .map(|ssid| ssid.trim().to_string())
+        })
+        .filter(|ssid| !ssid.is_empty());
+
+    WifiInfo { ssid, signal_dbm }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_wildcard_patterns() {
+        assert!(matches_pattern("docker0", "docker*"));
+        assert!(matches_pattern("wlan0", "*0"));
+        assert!(matches_pattern("eth0", "eth0"));
+        assert!(!matches_pattern("eth0", "eth"));
+        // Prefix and suffix must not overlap
+        assert!(!matches_pattern("ab", "ab*b"));
+    }
+
+    #[test]
+    fn appends_prefix_length() {
+        assert_eq!(
+            with_prefix("192.168.1.2".to_string(), Some(24)),
+            "192.168.1.2/24"
+        );
+        assert_eq!(with_prefix("fe80::1".to_string(), None), "fe80::1");
+    }
+}
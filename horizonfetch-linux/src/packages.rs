@@ -0,0 +1,175 @@
+// Installed package counts
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use crate::cache::{Cache, Hint};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Counts are invalidated by the package database's mtime, this only
+/// bounds how long a missed case can show a stale count.
+const PACKAGES_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+const PACMAN_DB: &str = "/var/lib/pacman/local";
+const DPKG_STATUS: &str = "/var/lib/dpkg/status";
+const APK_DB: &str = "/lib/apk/db/installed";
+const RPM_DB: &str = "/var/lib/rpm";
+const FLATPAK_SYSTEM: &str = "/var/lib/flatpak/app";
+
+#[derive(Serialize)]
+pub struct PackageCount {
+    pub manager: String,
+    pub count: usize,
+}
+
+/// Package managers with at least one installed package. Each count is
+/// cached until its database changes.
+pub fn get_packages(cache: &Cache) -> Vec<PackageCount> {
+    let flatpak_user = dirs::data_dir()
+        .map(|dir| dir.join("flatpak/app"))
+        .unwrap_or_default();
+    let flatpak = [PathBuf::from(FLATPAK_SYSTEM), flatpak_user];
+
+    let counts = [
+        (
+            "pacman",
+            count(cache, "pacman", &[PACMAN_DB], || {
+                count_dirs(Path::new(PACMAN_DB))
+            }),
+        ),
+        (
+            "dpkg",
+            count(cache, "dpkg", &[DPKG_STATUS], || {
+                Some(count_dpkg(&fs::read_to_string(DPKG_STATUS).ok()?))
+            }),
+        ),
+        (
+            "apk",
+            count(cache, "apk", &[APK_DB], || {
+                Some(count_apk(&fs::read_to_string(APK_DB).ok()?))
+            }),
+        ),
+        ("rpm", count(cache, "rpm", &[RPM_DB], count_rpm)),
+        (
+            "flatpak",
+            count(cache, "flatpak", &flatpak, || {
+                let counts: Vec<usize> = flatpak.iter().filter_map(|dir| count_dirs(dir)).collect();
+                (!counts.is_empty()).then(|| counts.iter().sum())
+            }),
+        ),
+    ];
+
+    counts
+        .into_iter()
+        .filter_map(|(manager, count)| {
+            Some(PackageCount {
+                manager: manager.to_string(),
+                count: count.filter(|&c| c > 0)?,
+            })
+        })
+        .collect()
+}
+
+/// Skips managers whose database does not exist without touching the
+/// cache, so installing one later is picked up right away.
+fn count<P: AsRef<Path>>(
+    cache: &Cache,
+    manager: &str,
+    databases: &[P],
+    run: impl FnOnce() -> Option<usize>,
+) -> Option<usize> {
+    let databases: Vec<&Path> = databases.iter().map(|p| p.as_ref()).collect();
+    if !databases.iter().any(|p| p.exists()) {
+        return None;
+    }
+    let hints: Vec<Hint> = databases.iter().map(|p| Hint::Mtime(p)).collect();
+    cache.get_opt(&format!("packages-{}", manager), PACKAGES_TTL, &hints, run)
+}
+
+/// pacman keeps one directory per package next to an `ALPM_DB_VERSION`
+/// file, flatpak one directory per app.
+fn count_dirs(dir: &Path) -> Option<usize> {
+    let entries = fs::read_dir(dir).ok()?;
+    Some(
+        entries
+            .flatten()
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .count(),
+    )
+}
+
+/// Counts stanzas that are fully installed, removed packages keep their
+/// stanza with `deinstall ok config-files`.
+fn count_dpkg(status: &str) -> usize {
+    status
+        .lines()
+        .filter(|line| {
+            line.strip_prefix("Status:")
+                .is_some_and(|s| s.trim().ends_with(" installed"))
+        })
+        .count()
+}
+
+fn count_apk(installed: &str) -> usize {
+    installed.lines().filter(|l| l.starts_with("P:")).count()
+}
+
+/// The rpm database is SQLite or Berkeley DB depending on the version, so
+/// ask rpm itself. This is the slow one the cache is for.
+fn count_rpm() -> Option<usize> {
+    let output = Command::new("rpm")
+        .args(["-qa", "--qf", "x\\n"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| output.stdout.iter().filter(|&&b| b == b'\n').count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::TempDir;
+
+    #[test]
+    fn counts_installed_dpkg_packages() {
+        let status = "\
+Package: bash
+Status: install ok installed
+Version: 5.2
+
+Package: old
+Status: deinstall ok config-files
+
+Package: coreutils
+Status: install ok installed
+";
+        assert_eq!(count_dpkg(status), 2);
+    }
+
+    #[test]
+    fn counts_apk_packages() {
+        assert_eq!(count_apk("C:Q1x\nP:musl\nV:1.2\n\nC:Q1y\nP:busybox\n"), 2);
+    }
+
+    #[test]
+    fn counts_package_directories() {
+        let dir = TempDir::with_files(
+            "packages",
+            &[
+                ("bash-5.2-1/desc", ""),
+                ("glibc-2.40-1/desc", ""),
+                ("ALPM_DB_VERSION", "9\n"),
+            ],
+        );
+        assert_eq!(count_dirs(dir.path()), Some(2));
+        let path = dir.path().to_path_buf();
+        drop(dir);
+        assert_eq!(count_dirs(&path), None);
+    }
+}
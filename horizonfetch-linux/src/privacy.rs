@@ -0,0 +1,230 @@
+// Redaction of personal data
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use crate::system::SystemInfo;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{Read, Write};
+use std::os::unix::fs::OpenOptionsExt;
+
+/// Unique per installation and not part of the output, see machine-id(5)
+const MACHINE_ID: [&str; 2] = ["/etc/machine-id", "/var/lib/dbus/machine-id"];
+
+/// What to mask when redaction is enabled.
+#[derive(Debug, Clone)]
+pub struct Privacy {
+    pub redact: bool,
+    pub username: bool,
+    pub hostname: bool,
+    /// IP addresses and Wi-Fi network names
+    pub network: bool,
+    /// Mount points below /home/<user> and other per-user directories
+    pub paths: bool,
+    /// MAC addresses of network interfaces
+    pub mac: bool,
+}
+
+impl Default for Privacy {
+    fn default() -> Self {
+        Privacy {
+            redact: false,
+            username: true,
+            hostname: true,
+            network: true,
+            paths: true,
+            mac: true,
+        }
+    }
+}
+
+/// Masks the selected fields in place. Replacements are derived from a hash
+/// of the original value keyed with a machine-local secret, so the same
+/// machine always redacts to the same output and runs can still be
+/// compared, but nobody without the secret can hash guesses to reverse them.
+pub fn redact(info: &mut SystemInfo, privacy: &Privacy) {
+    let masker = Masker::new(&secret());
+    let username = info.username.clone();
+
+    if privacy.paths && username != "unknown" {
+        for disk in &mut info.disks {
+            disk.name = masker.mask_path(&disk.name, &username);
+        }
+        for mount in info.storage.iter_mut().flat_map(|d| d.mounts.iter_mut()) {
+            mount.target = masker.mask_path(&mount.target, &username);
+        }
+        for tool in &mut info.toolchains {
+            tool.path = masker.mask_path(&tool.path, &username);
+        }
+    }
+
+    if privacy.username {
+        info.username = masker.mask("user", &info.username);
+    }
+    if privacy.hostname {
+        info.hostname = masker.mask("host", &info.hostname);
+    }
+
+    for iface in &mut info.network {
+        if privacy.network {
+            for addr in iface.ipv4.iter_mut() {
+                *addr = masker.mask_address("ip", addr);
+            }
+            for addr in iface.ipv6.iter_mut() {
+                *addr = masker.mask_address("ip6", addr);
+            }
+            if let Some(ref mut wifi) = iface.wifi {
+                wifi.ssid = wifi.ssid.as_deref().map(|ssid| masker.mask("ssid", ssid));
+            }
+        }
+        if privacy.mac {
+            iface.mac = iface.mac.as_deref().map(|mac| masker.mask("mac", mac));
+        }
+    }
+}
+
+/// Reads the machine id, or a random key stored in the config directory on
+/// systems without one. If neither works the key only lives for this run:
+/// values are still masked, but differ from the previous run.
+fn secret() -> Vec<u8> {
+    MACHINE_ID
+        .iter()
+        .find_map(|path| {
+            let id = fs::read_to_string(path).ok()?;
+            Some(id.trim().as_bytes().to_vec()).filter(|id| !id.is_empty())
+        })
+        .or_else(stored_key)
+        .unwrap_or_else(|| {
+            eprintln!(
+                "horizonfetch: could not store a redaction key, masks will differ between runs"
+            );
+            random_key()
+        })
+}
+
+fn stored_key() -> Option<Vec<u8>> {
+    let path = dirs::config_dir()?.join("horizonfetch/redact.key");
+    if let Ok(key) = fs::read(&path) {
+        return Some(key).filter(|key| !key.is_empty());
+    }
+    let key = random_key();
+    fs::create_dir_all(path.parent()?).ok()?;
+    // Readable by the owner only, anyone with the key can reverse masks
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&path)
+        .ok()?;
+    file.write_all(&key).ok()?;
+    Some(key)
+}
+
+/// 32 bytes from /dev/urandom, or from the randomly seeded std hasher when
+/// it cannot be read.
+fn random_key() -> Vec<u8> {
+    let mut key = vec![0; 32];
+    let read = fs::File::open("/dev/urandom").and_then(|mut file| file.read_exact(&mut key));
+    if read.is_err() {
+        let state = RandomState::new();
+        key = (0..4u64)
+            .flat_map(|i| {
+                let mut hasher = state.build_hasher();
+                hasher.write_u64(i);
+                hasher.finish().to_le_bytes()
+            })
+            .collect();
+    }
+    key
+}
+
+struct Masker {
+    key: Vec<u8>,
+}
+
+impl Masker {
+    fn new(secret: &[u8]) -> Self {
+        Masker {
+            key: secret.to_vec(),
+        }
+    }
+
+    /// `<kind>-<8 hex digits>` from HMAC-SHA256 over the value.
+    fn mask(&self, kind: &str, value: &str) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(value.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        format!(
+            "{}-{:02x}{:02x}{:02x}{:02x}",
+            kind, digest[0], digest[1], digest[2], digest[3]
+        )
+    }
+
+    /// Masks path components equal to the username, which covers
+    /// /home/<user> as well as /run/media/<user>.
+    fn mask_path(&self, path: &str, username: &str) -> String {
+        path.split('/')
+            .map(|part| {
+                if part == username {
+                    self.mask("user", part)
+                } else {
+                    part.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Replaces an address but keeps its prefix length, e.g.
+    /// `ip-1a2b3c4d/24`.
+    fn mask_address(&self, kind: &str, address: &str) -> String {
+        match address.split_once('/') {
+            Some((addr, prefix)) => format!("{}/{}", self.mask(kind, addr), prefix),
+            None => self.mask(kind, address),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_are_stable_per_key() {
+        let masker = Masker::new(b"0123456789abcdef");
+        let masked = masker.mask("host", "workstation");
+        assert_eq!(masked, masker.mask("host", "workstation"));
+        assert!(masked.starts_with("host-"));
+        assert_eq!(masked.len(), "host-".len() + 8);
+        assert_ne!(
+            masked,
+            Masker::new(b"another machine").mask("host", "workstation")
+        );
+    }
+
+    #[test]
+    fn random_keys_differ() {
+        let key = random_key();
+        assert_eq!(key.len(), 32);
+        assert_ne!(key, random_key());
+    }
+
+    #[test]
+    fn masked_output_never_contains_the_original() {
+        let masker = Masker::new(b"0123456789abcdef");
+        for value in ["alice", "192.168.1.20", "aa:bb:cc:dd:ee:ff"] {
+            assert!(!masker.mask("user", value).contains(value));
+        }
+
+        let path = masker.mask_path("/home/alice/data", "alice");
+        assert!(path.starts_with("/home/user-") && path.ends_with("/data"));
+        assert!(!path.contains("alice"));
+
+        let address = masker.mask_address("ip", "192.168.1.20/24");
+        assert!(address.starts_with("ip-") && address.ends_with("/24"));
+        assert!(!address.contains("192.168.1.20"));
+    }
+}
@@ -0,0 +1,78 @@
+// Running helper programs
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Output of a finished program.
+pub struct Output {
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Runs `command` and kills it if it does not exit within `timeout`, so a
+/// hung helper cannot stall the whole fetch. `None` when it could not be
+/// started or timed out.
+pub fn run_within(command: &mut Command, timeout: Duration) -> Option<Output> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(10)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+
+    let mut output = Output {
+        stdout: String::new(),
+        stderr: String::new(),
+    };
+    child
+        .stdout
+        .take()?
+        .read_to_string(&mut output.stdout)
+        .ok()?;
+    child
+        .stderr
+        .take()?
+        .read_to_string(&mut output.stderr)
+        .ok()?;
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_output() {
+        let output = run_within(
+            Command::new("sh").args(["-c", "echo out; echo err >&2"]),
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        assert_eq!(output.stdout, "out\n");
+        assert_eq!(output.stderr, "err\n");
+        assert!(run_within(&mut Command::new("/nonexistent"), Duration::from_secs(1)).is_none());
+    }
+
+    #[test]
+    fn kills_programs_that_hang() {
+        let start = Instant::now();
+        assert!(run_within(Command::new("sleep").arg("10"), Duration::from_millis(50)).is_none());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,169 @@
+// Security posture: LSMs, disk encryption and kernel lockdown
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use nix::libc;
+use serde::Serialize;
+use std::fs;
+use std::io::ErrorKind;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+/// Each check is `None` when it could not be performed, usually because we
+/// are not allowed to read the file, so a missing permission is never
+/// reported as "disabled".
+#[derive(Serialize)]
+pub struct SecurityInfo {
+    pub selinux: Option<String>,
+    pub apparmor: Option<String>,
+    /// Whether / sits on dm-crypt/LUKS, directly or below LVM/RAID
+    pub root_encrypted: Option<bool>,
+    pub lockdown: Option<String>,
+}
+
+impl SecurityInfo {
+    pub fn detect() -> Self {
+        SecurityInfo {
+            selinux: detect_selinux(),
+            apparmor: detect_apparmor(),
+            root_encrypted: detect_root_encryption(),
+            lockdown: detect_lockdown(),
+        }
+    }
+}
+
+/// Reads a file, mapping "does not exist" to `Ok(None)` and any other
+/// failure (usually EACCES) to `Err`.
+fn read(path: impl AsRef<Path>) -> Result<Option<String>, ()> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content.trim().to_string())),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(_) => Err(()),
+    }
+}
+
+fn detect_selinux() -> Option<String> {
+    match read("/sys/fs/selinux/enforce") {
+        Ok(Some(mode)) if mode == "1" => Some("enforcing".to_string()),
+        Ok(Some(_)) => Some("permissive".to_string()),
+        Ok(None) => Some("disabled".to_string()),
+        Err(()) => None,
+    }
+}
+
+fn detect_apparmor() -> Option<String> {
+    match read("/sys/module/apparmor/parameters/enabled") {
+        Ok(Some(enabled)) if enabled == "Y" => Some("enabled".to_string()),
+        Ok(Some(_)) | Ok(None) => Some("disabled".to_string()),
+        Err(()) => None,
+    }
+}
+
+fn detect_lockdown() -> Option<String> {
+    match read("/sys/kernel/security/lockdown") {
+        Ok(Some(modes)) => active_lockdown(&modes),
+        // The file is only missing for certain when securityfs is mounted,
+        // containers and minimal systems often lack the mount itself
+        Ok(None) => {
+            let mounts = fs::read_to_string("/proc/self/mounts").ok()?;
+            has_securityfs(&mounts).then(|| "unsupported".to_string())
+        }
+        Err(()) => None,
+    }
+}
+
+fn has_securityfs(mounts: &str) -> bool {
+    mounts.lines().any(|line| {
+        let mut parts = line.split_whitespace().skip(1);
+        parts.next() == Some("/sys/kernel/security") && parts.next() == Some("securityfs")
+    })
+}
+
+/// Lockdown mode, e.g. `[none] integrity confidentiality` -> `none`.
+fn active_lockdown(modes: &str) -> Option<String> {
+    modes
+        .split_whitespace()
+        .find(|m| m.starts_with('['))
+        .map(|m| m.trim_matches(|c| c == '[' || c == ']').to_string())
+}
+
+/// Finds the block device behind / and walks down device-mapper layers
+/// (LVM, RAID) looking for a dm-crypt target.
+fn detect_root_encryption() -> Option<bool> {
+    let device = root_device_from_stat().or_else(root_device_from_mounts)?;
+    is_encrypted(&device, 0).ok()
+}
+
+fn root_device_from_stat() -> Option<String> {
+    let meta = fs::metadata("/").ok()?;
+    let (major, minor) = (libc::major(meta.dev()), libc::minor(meta.dev()));
+    let path = fs::read_link(format!("/sys/dev/block/{}:{}", major, minor)).ok()?;
+    path.file_name()?.to_str().map(String::from)
+}
+
+/// btrfs reports an anonymous device number for /, so fall back to the
+/// source listed in the mount table.
+fn root_device_from_mounts() -> Option<String> {
+    let mounts = fs::read_to_string("/proc/self/mounts").ok()?;
+    let source = mounts
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some((parts.next()?, parts.next()?))
+        })
+        .filter(|(source, target)| *target == "/" && source.starts_with("/dev/"))
+        .map(|(source, _)| source)
+        .next_back()?;
+    let path = fs::canonicalize(source).ok()?;
+    path.file_name()?.to_str().map(String::from)
+}
+
+fn is_encrypted(device: &str, depth: usize) -> Result<bool, ()> {
+    // Guard against unexpected cycles in sysfs
+    if depth > 8 {
+        return Ok(false);
+    }
+    let block = Path::new("/sys/class/block").join(device);
+
+    // dm-crypt devices carry a "CRYPT-" prefix in their uuid
+    if let Some(uuid) = read(block.join("dm/uuid"))? {
+        if uuid.starts_with("CRYPT-") {
+            return Ok(true);
+        }
+    }
+
+    let slaves = match fs::read_dir(block.join("slaves")) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(_) => return Err(()),
+    };
+    for slave in slaves.flatten() {
+        if let Some(name) = slave.file_name().to_str() {
+            if is_encrypted(name, depth + 1)? {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_active_lockdown_mode() {
+        assert_eq!(
+            active_lockdown("none [integrity] confidentiality").as_deref(),
+            Some("integrity")
+        );
+        assert_eq!(active_lockdown("none integrity"), None);
+    }
+
+    #[test]
+    fn finds_the_securityfs_mount() {
+        let mounts = "sysfs /sys sysfs rw 0 0\n\
+                      securityfs /sys/kernel/security securityfs rw 0 0\n";
+        assert!(has_securityfs(mounts));
+        assert!(!has_securityfs("sysfs /sys sysfs rw 0 0\n"));
+    }
+}
@@ -0,0 +1,216 @@
+// Physical storage devices
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use crate::sysfs::read_attr;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const BLOCK_DIR: &str = "/sys/block";
+const CLASS_BLOCK_DIR: &str = "/sys/class/block";
+
+#[derive(Serialize)]
+pub struct StorageDevice {
+    pub name: String,
+    pub model: Option<String>,
+    pub vendor: Option<String>,
+    pub size: u64,
+    /// `None` when the kernel does not know, e.g. for some USB bridges
+    pub rotational: Option<bool>,
+    pub transport: Option<String>,
+    pub firmware: Option<String>,
+    pub mounts: Vec<Mount>,
+}
+
+/// A filesystem mounted from a partition, LVM volume or RAID array that
+/// lives on the device.
+#[derive(Serialize)]
+pub struct Mount {
+    pub source: String,
+    pub target: String,
+}
+
+pub fn get_storage() -> Vec<StorageDevice> {
+    let mut names: Vec<String> = fs::read_dir(BLOCK_DIR)
+        .map(|dir| {
+            dir.flatten()
+                .filter_map(|e| e.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+
+    let mounts = read_mounts();
+
+    names
+        .into_iter()
+        .filter(|name| !["loop", "ram", "zram"].iter().any(|p| name.starts_with(p)))
+        .filter_map(|name| {
+            let dir = Path::new(BLOCK_DIR).join(&name);
+            let real = fs::canonicalize(&dir).ok()?;
+            // dm, md, nbd and similar devices are built on top of physical ones
+            if real.starts_with("/sys/devices/virtual") {
+                return None;
+            }
+            // Sizes are always in 512-byte sectors
+            let size = read_attr(&dir, "size")?.parse::<u64>().ok()? * 512;
+            if size == 0 {
+                return None;
+            }
+
+            let real_path = real.to_string_lossy();
+            let transport = if name.starts_with("nvme") {
+                Some("NVMe")
+            } else if real_path.contains("/usb") {
+                Some("USB")
+            } else if real_path.contains("/ata") {
+                Some("SATA")
+            } else if real_path.contains("/virtio") {
+                Some("VirtIO")
+            } else if name.starts_with("mmcblk") {
+                Some("MMC")
+            } else {
+                None
+            };
+
+            let device_mounts = collect_names(&name)
+                .into_iter()
+                .flat_map(|source| {
+                    mounts
+                        .get(&source)
+                        .into_iter()
+                        .flatten()
+                        .map(move |target| Mount {
+                            source: display_name(&source),
+                            target: target.clone(),
+                        })
+                })
+                .collect();
+
+            Some(StorageDevice {
+                model: read_attr(&dir, "device/model"),
+                // SATA disks all report the libata placeholder "ATA" and
+                // virtio disks a raw PCI vendor id
+                vendor: read_attr(&dir, "device/vendor")
+                    .filter(|v| v != "ATA" && !v.starts_with("0x")),
+                size,
+                rotational: read_attr(&dir, "queue/rotational").map(|r| r == "1"),
+                transport: transport.map(String::from),
+                firmware: if name.starts_with("nvme") {
+                    read_attr(&dir, "device/firmware_rev")
+                } else {
+                    None
+                },
+                mounts: device_mounts,
+                name,
+            })
+        })
+        .collect()
+}
+
+/// Returns the device, its partitions and everything stacked on top of
+/// them (LVM volumes, RAID arrays, dm-crypt mappings).
+fn collect_names(device: &str) -> Vec<String> {
+    let mut names = vec![device.to_string()];
+    let dir = Path::new(BLOCK_DIR).join(device);
+    if let Ok(entries) = fs::read_dir(&dir) {
+        let mut partitions: Vec<String> = entries
+            .flatten()
+            .filter(|e| e.path().join("partition").exists())
+            .filter_map(|e| e.file_name().into_string().ok())
+            .collect();
+        partitions.sort();
+        names.extend(partitions);
+    }
+
+    let mut index = 0;
+    while index < names.len() {
+        let holders = Path::new(CLASS_BLOCK_DIR)
+            .join(&names[index])
+            .join("holders");
+        if let Ok(entries) = fs::read_dir(holders) {
+            for holder in entries.flatten() {
+                if let Ok(holder) = holder.file_name().into_string() {
+                    if !names.contains(&holder) {
+                        names.push(holder);
+                    }
+                }
+            }
+        }
+        index += 1;
+    }
+    names
+}
+
+/// Uses the device-mapper name (e.g. `vg0-home`) instead of `dm-3`.
+fn display_name(kernel_name: &str) -> String {
+    read_attr(&Path::new(CLASS_BLOCK_DIR).join(kernel_name), "dm/name")
+        .unwrap_or_else(|| kernel_name.to_string())
+}
+
+/// Maps kernel device names to their mount points.
+fn read_mounts() -> HashMap<String, Vec<String>> {
+    let mut mounts: HashMap<String, Vec<String>> = HashMap::new();
+    let content = fs::read_to_string("/proc/self/mounts").unwrap_or_default();
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(source), Some(target)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if !source.starts_with("/dev/") {
+            continue;
+        }
+        let Some(name) = fs::canonicalize(source)
+            .ok()
+            .and_then(|p| p.file_name()?.to_str().map(String::from))
+        else {
+            continue;
+        };
+        let target = unescape_mount(target);
+        let targets = mounts.entry(name).or_default();
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+    mounts
+}
+
+/// The mount table escapes space, tab, newline and backslash as octal,
+/// e.g. `/media/My\040Disk`.
+fn unescape_mount(field: &str) -> String {
+    let mut result = String::with_capacity(field.len());
+    let mut rest = field;
+    while let Some(pos) = rest.find('\\') {
+        result.push_str(&rest[..pos]);
+        let code = rest
+            .get(pos + 1..pos + 4)
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        match code {
+            Some(byte) => {
+                result.push(char::from(byte));
+                rest = &rest[pos + 4..];
+            }
+            None => {
+                result.push('\\');
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unescapes_mount_points() {
+        assert_eq!(unescape_mount(r"/media/My\040Disk"), "/media/My Disk");
+        assert_eq!(unescape_mount(r"/mnt/a\011b\134c"), "/mnt/a\tb\\c");
+        assert_eq!(unescape_mount("/mnt/plain"), "/mnt/plain");
+        // Not an octal escape, kept as is
+        assert_eq!(unescape_mount(r"/mnt/x\9"), r"/mnt/x\9");
+    }
+}
@@ -0,0 +1,20 @@
+// Reading sysfs, procfs and cgroupfs attributes
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use std::fs;
+use std::path::Path;
+
+/// Reads `dir/attr` without the trailing newline, `None` when it is missing
+/// or empty.
+pub fn read_attr(dir: &Path, attr: &str) -> Option<String> {
+    fs::read_to_string(dir.join(attr))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Reads a numeric attribute. Values that do not parse, like `max` in
+/// cgroup limits, are `None`.
+pub fn read_num(dir: &Path, attr: &str) -> Option<u64> {
+    read_attr(dir, attr)?.parse().ok()
+}
@@ -1,59 +1,199 @@
 // System information gathering
 // AnmiTaliDev <anmitalidev@nuros.org>
 
+use crate::audio::AudioInfo;
+use crate::battery::{get_power, PowerInfo};
+use crate::boot::BootInfo;
+use crate::cache::{Cache, Hint};
+use crate::cgroup::CgroupLimits;
+use crate::config::Config;
+use crate::cpu::{get_cpu, CpuInfo};
+use crate::locale::{get_keyboard_layout, get_locale, get_timezone, LocaleInfo};
+use crate::media::{get_media, MediaInfo};
+use crate::monitor::{connected_outputs, get_monitors, Monitor};
+use crate::network::{get_interfaces, NetworkInterface};
+use crate::packages::{get_packages, PackageCount};
+use crate::security::SecurityInfo;
+use crate::storage::{get_storage, StorageDevice};
+use crate::terminal::{query_palette, Palette, TerminalInfo};
+use crate::theme::ThemeInfo;
+use crate::toolchain::{get_toolchains, Toolchain};
+use crate::virt::VirtInfo;
+use serde::{Deserialize, Serialize};
 use std::fs;
+use std::time::Duration;
 use sysinfo::{Disks, System};
 
+const DMI_DIR: &str = "/sys/class/dmi/id";
+
+/// Hardware only changes across reboots, the boot hint does most of the
+/// invalidation
+const HARDWARE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Values OEMs leave in DMI fields they did not fill in
+const DMI_PLACEHOLDERS: [&str; 12] = [
+    "default string",
+    "to be filled by o.e.m.",
+    "o.e.m.",
+    "system product name",
+    "system version",
+    "system manufacturer",
+    "not applicable",
+    "not specified",
+    "none",
+    "type1productconfigid",
+    "x.x",
+    "0123456789",
+];
+
+#[derive(Serialize)]
 pub struct SystemInfo {
     pub username: String,
     pub hostname: String,
     pub os_name: String,
     pub kernel: String,
-    pub uptime: String,
+    pub uptime_secs: u64,
+    pub packages: Vec<PackageCount>,
+    pub boot_time: Option<i64>,
+    pub load: Option<LoadInfo>,
     pub shell: String,
+    pub terminal: TerminalInfo,
     pub de: String,
     pub screen: Option<String>,
+    pub monitors: Vec<Monitor>,
+    pub theme: ThemeInfo,
+    pub host: Option<String>,
     pub motherboard: Option<String>,
-    pub cpu: String,
-    pub gpu: Vec<String>,
-    pub ram_used_gb: f64,
-    pub ram_total_gb: f64,
-    pub ram_percent: f64,
-    pub swap_total_gb: f64,
-    pub locale: String,
+    pub bios: Option<String>,
+    pub virt: VirtInfo,
+    pub boot: BootInfo,
+    pub security: SecurityInfo,
+    pub cpu: CpuInfo,
+    pub gpu: Vec<GpuInfo>,
+    pub ram_used: u64,
+    pub ram_total: u64,
+    pub swap_total: u64,
+    pub cgroup: CgroupLimits,
+    pub locale: LocaleInfo,
+    pub timezone: Option<String>,
+    pub keyboard: Option<String>,
     pub disks: Vec<DiskInfo>,
+    pub storage: Vec<StorageDevice>,
+    pub power: PowerInfo,
+    pub audio: AudioInfo,
+    pub media: Option<MediaInfo>,
+    pub network: Vec<NetworkInterface>,
+    pub toolchains: Vec<Toolchain>,
+    pub palette: Option<Palette>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GpuInfo {
+    pub name: String,
+    /// Dedicated video memory, only amdgpu reports it in sysfs
+    pub vram: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct LoadInfo {
+    /// 1, 5 and 15 minute load averages
+    pub averages: [f64; 3],
+    pub running: u32,
+    pub total: u32,
 }
 
+#[derive(Serialize)]
 pub struct DiskInfo {
     pub name: String,
-    pub used_gb: u64,
-    pub total_gb: u64,
-    pub percent: u64,
+    pub used: u64,
+    pub total: u64,
 }
 
 impl SystemInfo {
-    pub fn gather() -> Self {
+    /// `everything` also runs the probes that are otherwise skipped while
+    /// their line is hidden, as `--json` prints every field.
+    pub fn gather(config: &Config, cache: &Cache, everything: bool) -> Self {
         let mut sys = System::new_all();
         sys.refresh_all();
 
+        // Nothing but placeholders is retried, the firmware tables may not
+        // have been readable yet
+        let (host, motherboard, bios) = cache
+            .get_opt("dmi", HARDWARE_TTL, &[Hint::Boot], || {
+                let dmi = (get_host(), get_motherboard(), get_bios());
+                (dmi != (None, None, None)).then_some(dmi)
+            })
+            .unwrap_or((None, None, None));
+
         SystemInfo {
             username: get_username(),
             hostname: get_hostname(),
             os_name: get_os_name(),
             kernel: get_kernel(),
-            uptime: get_uptime(&sys),
+            uptime_secs: System::uptime(),
+            packages: if config.show_packages || everything {
+                get_packages(cache)
+            } else {
+                Vec::new()
+            },
+            boot_time: get_boot_time(),
+            load: get_load(),
             shell: get_shell(),
+            terminal: TerminalInfo::detect(&sys),
             de: get_de(),
             screen: get_screen_resolution(),
-            motherboard: get_motherboard(),
+            monitors: cache.get(
+                "monitors",
+                HARDWARE_TTL,
+                &[Hint::Boot, Hint::Value(&connected_outputs())],
+                get_monitors,
+            ),
+            theme: ThemeInfo::detect(),
+            host,
+            motherboard,
+            bios,
+            virt: VirtInfo::detect(),
+            boot: BootInfo::detect(),
+            security: SecurityInfo::detect(),
             cpu: get_cpu(&sys),
-            gpu: get_gpu(),
-            ram_used_gb: get_ram_used(&sys),
-            ram_total_gb: get_ram_total(&sys),
-            ram_percent: get_ram_percent(&sys),
-            swap_total_gb: get_swap_total(&sys),
+            gpu: cache
+                .get_opt("gpu", HARDWARE_TTL, &[Hint::Boot], get_gpu)
+                .unwrap_or_else(|| {
+                    vec![GpuInfo {
+                        name: "Unknown".to_string(),
+                        vram: None,
+                    }]
+                }),
+            ram_used: sys.used_memory(),
+            ram_total: sys.total_memory(),
+            swap_total: sys.total_swap(),
+            cgroup: CgroupLimits::read(),
             locale: get_locale(),
+            timezone: get_timezone(),
+            keyboard: get_keyboard_layout(),
             disks: get_disks(),
+            storage: get_storage(),
+            power: get_power(),
+            audio: AudioInfo::detect(&sys),
+            // Talks to the session bus, so only ask when the line is shown
+            media: if config.show_media || everything {
+                get_media()
+            } else {
+                None
+            },
+            network: get_interfaces(),
+            // Runs every configured tool, so only when the line is shown
+            toolchains: if config.show_toolchains || everything {
+                get_toolchains(&config.toolchains, cache)
+            } else {
+                Vec::new()
+            },
+            // Writes escape sequences to the terminal and waits for a reply
+            palette: if config.show_color_scheme && config.query_palette {
+                query_palette()
+            } else {
+                None
+            },
         }
     }
 }
@@ -90,23 +230,64 @@ fn get_kernel() -> String {
         .to_string()
 }
 
-fn get_uptime(_sys: &System) -> String {
-    let uptime_sec = System::uptime();
-    let uptime_min = uptime_sec / 60;
-    let uptime_hr = uptime_min / 60;
-    let uptime_days = uptime_hr / 24;
+fn get_boot_time() -> Option<i64> {
+    let stat = fs::read_to_string("/proc/stat").ok()?;
+    stat.lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|t| t.trim().parse().ok())
+}
+
+fn get_load() -> Option<LoadInfo> {
+    parse_loadavg(&fs::read_to_string("/proc/loadavg").ok()?)
+}
 
-    if uptime_days > 0 {
-        format!("{}d {}h {}m", uptime_days, uptime_hr % 24, uptime_min % 60)
-    } else {
-        format!("{}h {}m", uptime_hr % 24, uptime_min % 60)
+/// Parses /proc/loadavg, e.g. `0.52 0.58 0.59 2/1234 56789`.
+fn parse_loadavg(content: &str) -> Option<LoadInfo> {
+    let mut parts = content.split_whitespace();
+    let one = parts.next()?.parse().ok()?;
+    let five = parts.next()?.parse().ok()?;
+    let fifteen = parts.next()?.parse().ok()?;
+    let (running, total) = parts.next()?.split_once('/')?;
+    Some(LoadInfo {
+        averages: [one, five, fifteen],
+        running: running.parse().ok()?,
+        total: total.parse().ok()?,
+    })
+}
+
+/// Formats a Unix timestamp with strftime in the local timezone and the
+/// LC_TIME locale of the environment.
+pub fn format_local_time(timestamp: i64, format: &str) -> Option<String> {
+    use nix::libc;
+    use std::ffi::{CStr, CString};
+
+    let format = CString::new(format).ok()?;
+    let time = timestamp as libc::time_t;
+    let mut buffer = [0 as libc::c_char; 128];
+
+    // SAFETY: all pointers are valid for the duration of the calls and the
+    // buffer length is passed to strftime.
+    unsafe {
+        libc::setlocale(libc::LC_TIME, c"".as_ptr());
+        let mut tm: libc::tm = std::mem::zeroed();
+        if libc::localtime_r(&time, &mut tm).is_null() {
+            return None;
+        }
+        let len = libc::strftime(buffer.as_mut_ptr(), buffer.len(), format.as_ptr(), &tm);
+        if len == 0 {
+            return None;
+        }
+        CStr::from_ptr(buffer.as_ptr())
+            .to_str()
+            .ok()
+            .map(String::from)
     }
 }
 
 fn get_shell() -> String {
     std::env::var("SHELL")
         .ok()
-        .and_then(|s| s.split('/').last().map(String::from))
+        .and_then(|s| s.split('/').next_back().map(String::from))
         .unwrap_or_else(|| "unknown".to_string())
 }
 
@@ -134,74 +315,110 @@ fn get_screen_resolution() -> Option<String> {
     None
 }
 
-fn get_motherboard() -> Option<String> {
-    fs::read_to_string("/sys/class/dmi/id/board_name")
-        .ok()
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty() && s != "Default string")
-}
-
-fn get_cpu(sys: &System) -> String {
-    sys.cpus()
-        .first()
-        .map(|cpu| {
-            let brand = cpu.brand().trim();
-            let count = sys.cpus().len();
-            if count > 1 {
-                format!("{} ({} threads)", brand, count)
-            } else {
-                brand.to_string()
-            }
-        })
-        .unwrap_or_else(|| "Unknown".to_string())
+pub fn read_dmi(field: &str) -> Option<String> {
+    clean_dmi(&fs::read_to_string(format!("{}/{}", DMI_DIR, field)).ok()?)
 }
 
-fn get_gpu() -> Vec<String> {
-    let mut gpus = Vec::new();
+/// Drops empty values and the OEM placeholders firmware leaves in place.
+fn clean_dmi(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty() && !DMI_PLACEHOLDERS.contains(&value.to_lowercase().as_str()))
+        .then(|| value.to_string())
+}
 
-    // Try lspci for GPU info
-    if let Ok(output) = std::process::Command::new("lspci").output() {
-        if let Ok(text) = String::from_utf8(output.stdout) {
-            for line in text.lines() {
-                if line.contains("VGA") || line.contains("3D") || line.contains("Display") {
-                    if let Some(gpu_name) = line.split(':').nth(2) {
-                        gpus.push(gpu_name.trim().to_string());
-                    }
-                }
-            }
+/// Joins DMI parts, dropping parts whose words already appear, as whole
+/// words and in order, in the previous ones.
+fn join_dmi(parts: &[Option<String>]) -> Option<String> {
+    let words =
+        |text: &str| -> Vec<String> { text.split_whitespace().map(str::to_lowercase).collect() };
+    let mut result = String::new();
+    for part in parts.iter().flatten() {
+        let (joined, part_words) = (words(&result), words(part));
+        if !part_words.is_empty()
+            && joined
+                .windows(part_words.len())
+                .any(|window| window == part_words)
+        {
+            continue;
+        }
+        if !result.is_empty() {
+            result.push(' ');
         }
+        result.push_str(part);
     }
+    Some(result).filter(|r| !r.is_empty())
+}
 
-    if gpus.is_empty() {
-        gpus.push("Unknown".to_string());
+fn get_host() -> Option<String> {
+    let name = read_dmi("product_name");
+    let version = read_dmi("product_version");
+    // Without a model there is nothing useful to show besides the vendor
+    if name.is_none() && version.is_none() {
+        return None;
     }
-
-    gpus
+    join_dmi(&[read_dmi("sys_vendor"), name, version])
 }
 
-fn get_ram_used(sys: &System) -> f64 {
-    (sys.used_memory() as f64) / 1_073_741_824.0
+fn get_motherboard() -> Option<String> {
+    let name = read_dmi("board_name")?;
+    join_dmi(&[read_dmi("board_vendor"), Some(name)])
 }
 
-fn get_ram_total(sys: &System) -> f64 {
-    (sys.total_memory() as f64) / 1_073_741_824.0
+fn get_bios() -> Option<String> {
+    let bios = join_dmi(&[read_dmi("bios_vendor"), read_dmi("bios_version")])?;
+    match read_dmi("bios_date") {
+        Some(date) => Some(format!("{} ({})", bios, date)),
+        None => Some(bios),
+    }
 }
 
-fn get_ram_percent(sys: &System) -> f64 {
-    let total = sys.total_memory() as f64;
-    if total > 0.0 {
-        (sys.used_memory() as f64 * 100.0) / total
-    } else {
-        0.0
+/// `None` when lspci is missing, fails or lists no display controller.
+fn get_gpu() -> Option<Vec<GpuInfo>> {
+    let output = std::process::Command::new("lspci").output().ok()?;
+    if !output.status.success() {
+        return None;
     }
+    let text = String::from_utf8(output.stdout).ok()?;
+
+    let gpus: Vec<GpuInfo> = parse_lspci(&text)
+        .into_iter()
+        .map(|(slot, name)| GpuInfo {
+            name: name.to_string(),
+            vram: read_vram(slot),
+        })
+        .collect();
+    (!gpus.is_empty()).then_some(gpus)
 }
 
-fn get_swap_total(sys: &System) -> f64 {
-    (sys.total_swap() as f64) / 1_073_741_824.0
+/// Slot and name of every display controller, e.g.
+/// `01:00.0 VGA compatible controller: AMD Navi 21 (rev c1)`.
+fn parse_lspci(text: &str) -> Vec<(&str, &str)> {
+    text.lines()
+        .filter_map(|line| {
+            let (slot, rest) = line.split_once(' ')?;
+            let (class, name) = rest.split_once(": ")?;
+            (class.contains("VGA") || class.contains("3D") || class.contains("Display"))
+                .then(|| (slot, name.trim()))
+        })
+        .collect()
 }
 
-fn get_locale() -> String {
-    std::env::var("LANG").unwrap_or_else(|_| "en_US.UTF-8".to_string())
+/// lspci leaves out PCI domain 0000, sysfs always includes it.
+fn read_vram(slot: &str) -> Option<u64> {
+    let address = if slot.matches(':').count() == 1 {
+        format!("0000:{}", slot)
+    } else {
+        slot.to_string()
+    };
+    fs::read_to_string(format!(
+        "/sys/bus/pci/devices/{}/mem_info_vram_total",
+        address
+    ))
+    .ok()?
+    .trim()
+    .parse()
+    .ok()
+    .filter(|&size| size > 0)
 }
 
 fn get_disks() -> Vec<DiskInfo> {
@@ -214,23 +431,83 @@ fn get_disks() -> Vec<DiskInfo> {
         let available_bytes = disk.available_space();
         let used_bytes = total_bytes.saturating_sub(available_bytes);
 
-        let total_gb = total_bytes / 1_073_741_824;
-        let used_gb = used_bytes / 1_073_741_824;
-        let percent = if total_gb > 0 {
-            (used_gb * 100) / total_gb
-        } else {
-            0
-        };
-
-        if total_gb > 0 {
+        if total_bytes > 0 {
             result.push(DiskInfo {
                 name,
-                used_gb,
-                total_gb,
-                percent,
+                used: used_bytes,
+                total: total_bytes,
             });
         }
     }
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_loadavg() {
+        let load = parse_loadavg("0.52 0.58 1.59 2/1234 56789\n").unwrap();
+        assert_eq!(load.averages, [0.52, 0.58, 1.59]);
+        assert_eq!((load.running, load.total), (2, 1234));
+        assert!(parse_loadavg("0.52 0.58").is_none());
+    }
+
+    #[test]
+    fn filters_dmi_placeholders() {
+        assert_eq!(clean_dmi("ThinkPad X1\n").as_deref(), Some("ThinkPad X1"));
+        assert_eq!(clean_dmi("To Be Filled By O.E.M.\n"), None);
+        assert_eq!(clean_dmi("  "), None);
+    }
+
+    #[test]
+    fn joins_dmi_parts_without_repeats() {
+        let part = |s: &str| Some(s.to_string());
+        assert_eq!(
+            join_dmi(&[part("ThinkPad X1 Carbon"), part("ThinkPad"), None]).as_deref(),
+            Some("ThinkPad X1 Carbon")
+        );
+        assert_eq!(
+            join_dmi(&[part("Dell Inc."), part("XPS 13")]).as_deref(),
+            Some("Dell Inc. XPS 13")
+        );
+        assert_eq!(join_dmi(&[None, None]), None);
+        // Only whole words count as repeats
+        assert_eq!(
+            join_dmi(&[part("LENOVO"), part("ThinkPad X1"), part("1")]).as_deref(),
+            Some("LENOVO ThinkPad X1 1")
+        );
+        assert_eq!(
+            join_dmi(&[part("ASUS"), part("ProArt B650"), part("Pro")]).as_deref(),
+            Some("ASUS ProArt B650 Pro")
+        );
+        assert_eq!(
+            join_dmi(&[part("LENOVO"), part("Legion 5 Pro"), part("legion 5")]).as_deref(),
+            Some("LENOVO Legion 5 Pro")
+        );
+    }
+
+    #[test]
+    fn parses_display_controllers() {
+        let text = "\
+00:02.0 VGA compatible controller: Intel Corporation Alder Lake-P GT2 [Iris Xe Graphics] (rev 0c)
+00:1f.3 Audio device: Intel Corporation Alder Lake PCH-P High Definition Audio Controller (rev 01)
+0000:01:00.0 3D controller: NVIDIA Corporation GA107M [GeForce RTX 3050 Mobile] (rev a1)
+";
+        assert_eq!(
+            parse_lspci(text),
+            [
+                (
+                    "00:02.0",
+                    "Intel Corporation Alder Lake-P GT2 [Iris Xe Graphics] (rev 0c)"
+                ),
+                (
+                    "0000:01:00.0",
+                    "NVIDIA Corporation GA107M [GeForce RTX 3050 Mobile] (rev a1)"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,506 @@
+// Terminal emulator, font and color palette
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use nix::libc;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::{IsTerminal, Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+use sysinfo::System;
+
+/// How long to wait for the terminal to answer queries. Terminals
+/// that do not support them are detected by the device attributes reply
+/// arriving first, so this only matters for very slow connections.
+const QUERY_TIMEOUT: Duration = Duration::from_millis(150);
+
+/// Processes between us and the terminal emulator that are not terminals
+const NON_TERMINALS: [&str; 20] = [
+    "bash",
+    "zsh",
+    "fish",
+    "sh",
+    "dash",
+    "ksh",
+    "mksh",
+    "tcsh",
+    "csh",
+    "nu",
+    "elvish",
+    "xonsh",
+    "sudo",
+    "doas",
+    "su",
+    "login",
+    "script",
+    "cargo",
+    "horizonfetch",
+    "env",
+];
+
+#[derive(Serialize)]
+pub struct TerminalInfo {
+    pub name: Option<String>,
+    /// Family and size, e.g. `JetBrains Mono 11`
+    pub font: Option<String>,
+}
+
+pub type Rgb = (u8, u8, u8);
+
+/// Colors reported by the terminal itself, `None` for entries it did not
+/// answer.
+#[derive(Serialize)]
+pub struct Palette {
+    pub colors: [Option<Rgb>; 16],
+    pub foreground: Option<Rgb>,
+    pub background: Option<Rgb>,
+}
+
+impl TerminalInfo {
+    pub fn detect(sys: &System) -> Self {
+        let name = detect_from_env().or_else(|| detect_from_parents(sys));
+        let font = name.as_deref().and_then(read_font);
+        TerminalInfo { name, font }
+    }
+}
+
+/// Most terminals identify themselves through the environment.
+fn detect_from_env() -> Option<String> {
+    let set = |var: &str| std::env::var_os(var).is_some_and(|v| !v.is_empty());
+
+    if set("KITTY_WINDOW_ID") {
+        return Some("kitty".to_string());
+    }
+    if set("ALACRITTY_WINDOW_ID") || set("ALACRITTY_SOCKET") {
+        return Some("alacritty".to_string());
+    }
+    if set("WEZTERM_EXECUTABLE") {
+        return Some("wezterm".to_string());
+    }
+    if set("GNOME_TERMINAL_SCREEN") {
+        return Some("gnome-terminal".to_string());
+    }
+    if set("KONSOLE_VERSION") {
+        return Some("konsole".to_string());
+    }
+    if std::env::var("TERM").is_ok_and(|t| t == "foot" || t.starts_with("foot-")) {
+        return Some("foot".to_string());
+    }
+    // tmux and screen set TERM_PROGRAM for themselves, the parent walk
+    // finds the real terminal below them
+    std::env::var("TERM_PROGRAM")
+        .ok()
+        .filter(|t| !t.is_empty() && t != "tmux" && t != "screen")
+        .map(|t| t.to_lowercase())
+}
+
+/// Walks up the process tree to the first process that is not a shell or
+/// a wrapper like sudo.
+fn detect_from_parents(sys: &System) -> Option<String> {
+    let mut pid = sysinfo::get_current_pid().ok()?;
+    // Guard against unexpected cycles
+    for _ in 0..16 {
+        let process = sys.process(pid)?;
+        let parent = sys.process(process.parent()?)?;
+        let name = parent.name().to_string_lossy().into_owned();
+        if name == "systemd" || name == "init" || name.starts_with("sshd") {
+            return None;
+        }
+        if !NON_TERMINALS.contains(&name.trim_start_matches('-')) {
+            // Process names are cut to 15 bytes by the kernel
+            return Some(match name.as_str() {
+                "gnome-terminal-" => "gnome-terminal".to_string(),
+                "kgx" => "gnome-console".to_string(),
+                "wezterm-gui" => "wezterm".to_string(),
+                _ => name,
+            });
+        }
+        pid = parent.pid();
+    }
+    None
+}
+
+fn read_font(terminal: &str) -> Option<String> {
+    let config = dirs::config_dir()?;
+    match terminal {
+        "kitty" => read_kitty_font(&config.join("kitty/kitty.conf")),
+        "alacritty" => read_alacritty_font(&config.join("alacritty/alacritty.toml")),
+        "foot" => read_foot_font(&config.join("foot/foot.ini")),
+        "wezterm" => read_wezterm_font(&[
+            config.join("wezterm/wezterm.lua"),
+            dirs::home_dir()?.join(".wezterm.lua"),
+        ]),
+        "gnome-terminal" => read_gnome_terminal_font(),
+        _ => None,
+    }
+}
+
+/// kitty.conf uses `font_family JetBrains Mono` and `font_size 11.0`.
+fn read_kitty_font(path: &Path) -> Option<String> {
+    kitty_font(&fs::read_to_string(path).ok()?)
+}
+
+fn kitty_font(content: &str) -> Option<String> {
+    let value = |key: &str| {
+        content.lines().rev().find_map(|line| {
+            let (k, v) = line.trim().split_once(char::is_whitespace)?;
+            (k == key).then(|| v.trim().to_string())
+        })
+    };
+    let family = value("font_family").filter(|f| f != "monospace")?;
+    // kitty's default size
+    let size = value("font_size").unwrap_or_else(|| "11.0".to_string());
+    Some(format!("{} {}", family, trim_size(&size)))
+}
+
+/// Handles both `[font.normal]` tables and inline `normal = { family = "..." }`.
+fn read_alacritty_font(path: &Path) -> Option<String> {
+    alacritty_font(&fs::read_to_string(path).ok()?)
+}
+
+fn alacritty_font(content: &str) -> Option<String> {
+    let mut section = "";
+    let mut family = None;
+    let mut size = None;
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name;
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        match (section, key) {
+            ("font.normal", "family") => family = Some(unquote(value)),
+            ("font", "normal") => family = inline_family(value).or(family),
+            ("font", "size") => size = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    // Alacritty's default size
+    let size = size.unwrap_or_else(|| "11.25".to_string());
+    Some(format!("{} {}", family?, trim_size(&size)))
+}
+
+fn inline_family(table: &str) -> Option<String> {
+    let (_, rest) = table.split_once("family")?;
+    let (_, value) = rest.split_once('=')?;
+    let value = value.trim_start().strip_prefix('"')?;
+    value.split_once('"').map(|(family, _)| family.to_string())
+}
+
+/// foot.ini: `font=Fira Code:size=10, Noto Color Emoji` under `[main]`
+/// or before any section.
+fn read_foot_font(path: &Path) -> Option<String> {
+    foot_font(&fs::read_to_string(path).ok()?)
+}
+
+fn foot_font(content: &str) -> Option<String> {
+    let mut section = "main";
+    let font = content.lines().find_map(|line| {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name;
+            return None;
+        }
+        let (key, value) = line.split_once('=')?;
+        (section == "main" && key.trim() == "font").then(|| value.trim().to_string())
+    })?;
+
+    let first = font.split(',').next()?.trim();
+    let mut parts = first.split(':');
+    let family = parts.next()?.trim();
+    let size = parts
+        .find_map(|p| p.trim().strip_prefix("size="))
+        .unwrap_or("8");
+    Some(format!("{} {}", family, trim_size(size)))
+}
+
+/// Only the simple cases: `wezterm.font("Name")` or `wezterm.font 'Name'`
+/// together with `font_size = 12.0`.
+fn read_wezterm_font(paths: &[PathBuf]) -> Option<String> {
+    wezterm_font(&paths.iter().find_map(|p| fs::read_to_string(p).ok())?)
+}
+
+fn wezterm_font(content: &str) -> Option<String> {
+    let (_, rest) = content.split_once("wezterm.font")?;
+    let rest = rest.trim_start_matches(['(', ' ', '{']);
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let (family, _) = rest[1..].split_once(quote)?;
+
+    let size = content.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim().trim_start_matches("config.") == "font_size")
+            .then(|| value.trim().trim_end_matches([',', ';']).to_string())
+    });
+    // WezTerm's default size
+    let size = size.unwrap_or_else(|| "12.0".to_string());
+    Some(format!("{} {}", family, trim_size(&size)))
+}
+
+/// Reads the default profile from `dconf dump`, falling back to the system
+/// monospace font that profiles use unless told otherwise.
+fn read_gnome_terminal_font() -> Option<String> {
+    let dump = dconf(&["dump", "/org/gnome/terminal/legacy/profiles:/"]).unwrap_or_default();
+    let mut default = None;
+    let mut section = String::new();
+    let mut profiles: Vec<(String, String, String)> = Vec::new();
+    for line in dump.lines() {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim_start_matches(':').to_string();
+        } else if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim_matches('\'').to_string();
+            if section == "/" && key == "default" {
+                default = Some(value);
+            } else {
+                profiles.push((section.clone(), key.to_string(), value));
+            }
+        }
+    }
+
+    let profile_value = |key: &str| {
+        let profile = default.as_deref()?;
+        profiles
+            .iter()
+            .find(|(section, k, _)| section == profile && k == key)
+            .map(|(_, _, value)| value.clone())
+    };
+    if profile_value("use-system-font").as_deref() == Some("false") {
+        if let Some(font) = profile_value("font") {
+            return Some(font);
+        }
+    }
+    dconf(&["read", "/org/gnome/desktop/interface/monospace-font-name"])
+        .map(|font| font.trim().trim_matches('\'').to_string())
+        .filter(|font| !font.is_empty())
+}
+
+fn dconf(args: &[&str]) -> Option<String> {
+    let output = Command::new("dconf").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+fn unquote(value: &str) -> String {
+    value.trim_matches(|c| c == '"' || c == '\'').to_string()
+}
+
+/// `11.0` -> `11`, `11.25` stays as is.
+fn trim_size(size: &str) -> String {
+    let size = unquote(size.trim());
+    match size.strip_suffix(".0") {
+        Some(whole) => whole.to_string(),
+        None => size,
+    }
+}
+
+/// Asks the terminal for its 16 colors (OSC 4) and default foreground and
+/// background (OSC 10/11). Returns `None` when stdout is not a terminal or
+/// nothing was answered.
+pub fn query_palette() -> Option<Palette> {
+    let mut query = String::new();
+    for index in 0..16 {
+        query.push_str(&format!("\x1b]4;{};?\x1b\\", index));
+    }
+    query.push_str("\x1b]10;?\x1b\\\x1b]11;?\x1b\\");
+    parse_palette(&query_terminal(&query)?)
+}
+
+/// Collects the OSC 4/10/11 replies, skipping any that are malformed.
+fn parse_palette(response: &str) -> Option<Palette> {
+    let mut palette = Palette {
+        colors: [None; 16],
+        foreground: None,
+        background: None,
+    };
+    for reply in response.split("\x1b]").skip(1) {
+        let reply = reply.split(['\x07', '\x1b']).next().unwrap_or("");
+        let mut parts = reply.split(';');
+        match parts.next() {
+            Some("4") => {
+                let Some(index) = parts.next().and_then(|i| i.parse::<usize>().ok()) else {
+                    continue;
+                };
+                if let Some(slot) = palette.colors.get_mut(index) {
+                    *slot = parts.next().and_then(parse_color);
+                }
+            }
+            Some("10") => palette.foreground = parts.next().and_then(parse_color),
+            Some("11") => palette.background = parts.next().and_then(parse_color),
+            _ => {}
+        }
+    }
+
+    let answered = palette.colors.iter().any(Option::is_some)
+        || palette.foreground.is_some()
+        || palette.background.is_some();
+    answered.then_some(palette)
+}
+
+/// Whether the terminal lists sixel graphics (attribute 4) in its primary
+/// device attributes.
+pub fn supports_sixel() -> bool {
+    query_terminal("").is_some_and(|response| lists_sixel(&response))
+}
+
+/// Looks for attribute 4 in a reply like `ESC [ ? 62 ; 4 ; 22 c`.
+fn lists_sixel(response: &str) -> bool {
+    response
+        .split("\x1b[?")
+        .nth(1)
+        .and_then(|attrs| attrs.split('c').next())
+        .is_some_and(|attrs| attrs.split(';').any(|a| a == "4"))
+}
+
+/// Sends `query` followed by a primary device attributes request and
+/// returns everything the terminal answered. Every terminal answers the
+/// device attributes request, so its reply marks the end of whatever the
+/// terminal was willing to answer. `None` when stdout is not a terminal.
+fn query_terminal(query: &str) -> Option<String> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+
+    enable_raw_mode().ok()?;
+    let response = tty
+        .write_all(format!("{}\x1b[c", query).as_bytes())
+        .and_then(|_| tty.flush())
+        .ok()
+        .map(|_| read_response(&mut tty));
+    let _ = disable_raw_mode();
+    response
+}
+
+/// Reads until the device attributes reply (`ESC [ ? ... c`) or the timeout.
+fn read_response(tty: &mut fs::File) -> String {
+    let deadline = Instant::now() + QUERY_TIMEOUT;
+    let mut response = Vec::new();
+    let mut buffer = [0u8; 1024];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        let mut fds = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: fds points to one valid pollfd for the duration of the call
+        let ready = unsafe { libc::poll(&mut fds, 1, remaining.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            break;
+        }
+        match tty.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => response.extend_from_slice(&buffer[..n]),
+        }
+        if let Some(start) = find(&response, b"\x1b[?") {
+            if response[start..].contains(&b'c') {
+                break;
+            }
+        }
+    }
+    String::from_utf8_lossy(&response).into_owned()
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Parses `rgb:RRRR/GGGG/BBBB`, where each channel has 1 to 4 hex digits.
+fn parse_color(spec: &str) -> Option<Rgb> {
+    let channels = spec.strip_prefix("rgb:")?;
+    let mut parts = channels.split('/').map(|channel| {
+        let digits = channel.len() as u32;
+        if !(1..=4).contains(&digits) {
+            return None;
+        }
+        let value = u32::from_str_radix(channel, 16).ok()?;
+        let max = 16u32.pow(digits) - 1;
+        Some((value * 255 / max) as u8)
+    });
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_color_specs() {
+        assert_eq!(parse_color("rgb:ffff/8080/0000"), Some((255, 128, 0)));
+        assert_eq!(parse_color("rgb:f/8/0"), Some((255, 136, 0)));
+        assert_eq!(parse_color("rgb:ff/00"), None);
+        assert_eq!(parse_color("rgb:fffff/0/0"), None);
+        assert_eq!(parse_color("#ff0000"), None);
+    }
+
+    #[test]
+    fn parses_palette_replies() {
+        let response = concat!(
+            "\x1b]4;1;rgb:cccc/0000/0000\x1b\\",
+            "\x1b]4;x;rgb:ffff/ffff/ffff\x07",
+            "\x1b]4;2;rgb:0000/cccc/0000\x07",
+            "\x1b]11;rgb:1e1e/1e1e/2e2e\x1b\\",
+            "\x1b[?62;4c",
+        );
+        let palette = parse_palette(response).unwrap();
+        // The malformed reply is skipped, the ones around it are kept
+        assert_eq!(palette.colors[1], Some((204, 0, 0)));
+        assert_eq!(palette.colors[2], Some((0, 204, 0)));
+        assert_eq!(palette.colors[0], None);
+        assert_eq!(palette.foreground, None);
+        assert_eq!(palette.background, Some((30, 30, 46)));
+        assert!(parse_palette("\x1b[?62c").is_none());
+    }
+
+    #[test]
+    fn finds_sixel_in_device_attributes() {
+        assert!(lists_sixel("\x1b[?62;4;22c"));
+        assert!(!lists_sixel("\x1b[?62;22;44c"));
+        assert!(!lists_sixel("\x1b[?1;2c"));
+        assert!(!lists_sixel(""));
+    }
+
+    #[test]
+    fn reads_terminal_fonts() {
+        assert_eq!(
+            kitty_font("font_family JetBrains Mono\nfont_size 11.0\n").as_deref(),
+            Some("JetBrains Mono 11")
+        );
+        assert_eq!(kitty_font("font_family monospace\n"), None);
+        assert_eq!(
+            alacritty_font("[font]\nsize = 10.5\n[font.normal]\nfamily = \"Hack\"\n").as_deref(),
+            Some("Hack 10.5")
+        );
+        assert_eq!(
+            alacritty_font("[font]\nnormal = { family = \"Iosevka\", style = \"Regular\" }\n")
+                .as_deref(),
+            Some("Iosevka 11.25")
+        );
+        assert_eq!(
+            foot_font("font=Fira Code:size=10, Noto Color Emoji\n[colors]\n").as_deref(),
+            Some("Fira Code 10")
+        );
+        assert_eq!(
+            wezterm_font(
+                "config.font = wezterm.font(\"Cascadia Code\")\nconfig.font_size = 13.0\n"
+            )
+            .as_deref(),
+            Some("Cascadia Code 13")
+        );
+    }
+}
@@ -0,0 +1,45 @@
+// Shared test fixtures
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A fresh directory below the system temp dir. It is removed on drop, so
+/// a failing assertion does not leave it behind.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// `name` has to be unique among the tests, they run in parallel.
+    pub fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("horizonfetch-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    /// Creates the directory with `files`, given relative to it.
+    pub fn with_files(name: &str, files: &[(&str, &str)]) -> Self {
+        let dir = TempDir::new(name);
+        for (file, content) in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.0.join(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
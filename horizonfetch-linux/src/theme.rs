@@ -0,0 +1,181 @@
+// GTK and Qt theme, icons, cursor and font
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize)]
+pub struct ToolkitTheme {
+    pub theme: Option<String>,
+    pub icons: Option<String>,
+    pub cursor: Option<String>,
+    pub font: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ThemeInfo {
+    pub gtk: ToolkitTheme,
+    pub qt: ToolkitTheme,
+}
+
+impl ThemeInfo {
+    pub fn detect() -> Self {
+        let config = dirs::config_dir().unwrap_or_default();
+        let home = dirs::home_dir().unwrap_or_default();
+
+        let mut gtk = read_gtk(&config, &home);
+        let mut qt = read_qt(&config);
+
+        // The XCursor default applies to both toolkits
+        let default_cursor = std::env::var("XCURSOR_THEME")
+            .ok()
+            .filter(|c| !c.is_empty())
+            .or_else(|| {
+                read_ini(
+                    &home.join(".icons/default/index.theme"),
+                    "Icon Theme",
+                    "Inherits",
+                )
+            });
+        gtk.cursor = gtk.cursor.or_else(|| default_cursor.clone());
+        qt.cursor = qt.cursor.or(default_cursor);
+
+        ThemeInfo { gtk, qt }
+    }
+}
+
+fn read_gtk(config: &Path, home: &Path) -> ToolkitTheme {
+    let sources = [
+        config.join("gtk-3.0/settings.ini"),
+        config.join("gtk-4.0/settings.ini"),
+    ];
+    let gtk2 = home.join(".gtkrc-2.0");
+
+    let lookup = |key: &str| {
+        sources
+            .iter()
+            .find_map(|path| read_ini(path, "Settings", key))
+            .or_else(|| read_gtkrc(&gtk2, key))
+    };
+
+    ToolkitTheme {
+        theme: lookup("gtk-theme-name"),
+        icons: lookup("gtk-icon-theme-name"),
+        cursor: lookup("gtk-cursor-theme-name"),
+        font: lookup("gtk-font-name"),
+    }
+}
+
+fn read_qt(config: &Path) -> ToolkitTheme {
+    let kdeglobals = config.join("kdeglobals");
+    // qt6ct takes precedence over qt5ct when both are configured
+    let qtct: Vec<PathBuf> = ["qt6ct/qt6ct.conf", "qt5ct/qt5ct.conf"]
+        .iter()
+        .map(|p| config.join(p))
+        .collect();
+    let qtct_value =
+        |section: &str, key: &str| qtct.iter().find_map(|path| read_ini(path, section, key));
+
+    ToolkitTheme {
+        theme: read_ini(&kdeglobals, "KDE", "widgetStyle")
+            .or_else(|| read_ini(&kdeglobals, "General", "widgetStyle"))
+            .or_else(|| qtct_value("Appearance", "style")),
+        icons: read_ini(&kdeglobals, "Icons", "Theme")
+            .or_else(|| qtct_value("Appearance", "icon_theme")),
+        cursor: read_ini(&config.join("kcminputrc"), "Mouse", "cursorTheme"),
+        font: read_ini(&kdeglobals, "General", "font")
+            .or_else(|| qtct_value("Fonts", "general"))
+            .map(|font| format_qt_font(&font)),
+    }
+}
+
+/// Qt stores fonts as `"Noto Sans,10,-1,5,50,0,0,0,0,0"`.
+fn format_qt_font(font: &str) -> String {
+    let font = font.trim_matches('"');
+    let mut parts = font.split(',');
+    match (parts.next(), parts.next()) {
+        (Some(family), Some(size)) => format!("{} {}", family, size),
+        _ => font.to_string(),
+    }
+}
+
+fn read_ini(path: &Path, section: &str, key: &str) -> Option<String> {
+    ini_value(&fs::read_to_string(path).ok()?, section, key)
+}
+
+fn ini_value(content: &str, section: &str, key: &str) -> Option<String> {
+    let mut current = "";
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = name;
+        } else if current == section {
+            if let Some((k, v)) = line.split_once('=') {
+                let value = v.trim().trim_matches('"');
+                if k.trim() == key && !value.is_empty() {
+                    return Some(value.to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// GTK 2 rc files have no sections: `gtk-theme-name="Adwaita"`.
+fn read_gtkrc(path: &Path, key: &str) -> Option<String> {
+    gtkrc_value(&fs::read_to_string(path).ok()?, key)
+}
+
+fn gtkrc_value(content: &str, key: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let (k, v) = line.trim().split_once('=')?;
+        let value = v.trim().trim_matches('"');
+        (k.trim() == key && !value.is_empty()).then(|| value.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_ini_values_from_their_section() {
+        let content = concat!(
+            "[General]\n",
+            "font=\"Noto Sans,10,-1,5,50,0,0,0,0,0\"\n",
+            "[Icons]\n",
+            "Theme = breeze-dark\n",
+            "Empty=\n",
+        );
+        assert_eq!(
+            ini_value(content, "Icons", "Theme").as_deref(),
+            Some("breeze-dark")
+        );
+        assert_eq!(ini_value(content, "General", "Theme"), None);
+        assert_eq!(ini_value(content, "Icons", "Empty"), None);
+    }
+
+    #[test]
+    fn reads_gtkrc_values() {
+        let content = "gtk-theme-name=\"Adwaita\"\ngtk-icon-theme-name = \"Papirus\"\n";
+        assert_eq!(
+            gtkrc_value(content, "gtk-theme-name").as_deref(),
+            Some("Adwaita")
+        );
+        assert_eq!(
+            gtkrc_value(content, "gtk-icon-theme-name").as_deref(),
+            Some("Papirus")
+        );
+        assert_eq!(gtkrc_value(content, "gtk-font-name"), None);
+    }
+
+    #[test]
+    fn formats_qt_fonts() {
+        assert_eq!(
+            format_qt_font("\"Noto Sans,10,-1,5,50,0,0,0,0,0\""),
+            "Noto Sans 10"
+        );
+        assert_eq!(format_qt_font("Hack"), "Hack");
+    }
+}
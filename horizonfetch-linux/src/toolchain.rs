@@ -0,0 +1,191 @@
+// Developer toolchain versions
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use crate::cache::{Cache, Hint};
+use crate::process::run_within;
+use serde::Serialize;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+/// Upper bound for a single `--version` call. JVM startup is the slowest
+/// of the supported tools.
+const VERSION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Versions are also invalidated by the hints, this only bounds how long a
+/// missed case can show a stale version.
+const TOOLCHAIN_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Tool name as used in the config, binaries to look for and the arguments
+/// that print the version.
+const TOOLS: [(&str, &[&str], &[&str]); 8] = [
+    ("rustc", &["rustc"], &["--version"]),
+    ("cargo", &["cargo"], &["--version"]),
+    ("gcc", &["gcc"], &["--version"]),
+    ("clang", &["clang"], &["--version"]),
+    ("python", &["python3", "python"], &["--version"]),
+    ("node", &["node", "nodejs"], &["--version"]),
+    ("go", &["go"], &["version"]),
+    // -version works on every JDK, --version only since 9
+    ("java", &["java"], &["-version"]),
+];
+
+#[derive(Serialize)]
+pub struct Toolchain {
+    pub name: String,
+    pub version: String,
+    pub path: String,
+}
+
+/// Returns the installed tools out of `names`, in the order given. Only
+/// tools that changed since the last run are executed.
+pub fn get_toolchains(names: &[String], cache: &Cache) -> Vec<Toolchain> {
+    let found: Vec<(&str, PathBuf, &[&str])> = names
+        .iter()
+        .filter_map(|name| TOOLS.iter().find(|(tool, _, _)| tool == name))
+        .filter_map(|(name, binaries, args)| {
+            let path = binaries.iter().find_map(|b| find_in_path(b))?;
+            Some((*name, path, *args))
+        })
+        .collect();
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = found
+            .iter()
+            .map(|(name, path, args)| {
+                scope.spawn(move || {
+                    let version = cached_version(cache, name, path, args)?;
+                    Some(Toolchain {
+                        name: name.to_string(),
+                        version,
+                        path: path.to_string_lossy().into_owned(),
+                    })
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|h| h.join().ok().flatten())
+            .collect()
+    })
+}
+
+/// Upgrading a tool changes the binary's mtime. Version manager shims
+/// never change themselves, so the manager's own state is a hint for them.
+fn cached_version(cache: &Cache, name: &str, path: &Path, args: &[&str]) -> Option<String> {
+    let real = fs::canonicalize(path).ok()?;
+    let state = manager_state(path, &real);
+    let path_str = path.to_string_lossy();
+    let mut hints = vec![Hint::Value(&path_str), Hint::Mtime(&real)];
+    hints.extend(state.iter().map(|p| Hint::Mtime(p)));
+
+    cache.get_opt(
+        &format!("toolchain-{}", name),
+        TOOLCHAIN_TTL,
+        &hints,
+        || run_version(path, args),
+    )
+}
+
+fn find_in_path(binary: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(binary))
+        .find(|candidate| {
+            fs::metadata(candidate)
+                .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        })
+}
+
+/// Runs the tool and kills it if it does not answer within the timeout.
+fn run_version(path: &Path, args: &[&str]) -> Option<String> {
+    let output = run_within(Command::new(path).args(args), VERSION_TIMEOUT)?;
+    // Python 2 and java print their version on stderr
+    parse_version(&format!("{}{}", output.stdout, output.stderr))
+}
+
+/// Picks the version number out of the first line:
+/// `gcc (GCC) 14.1.1 20240522` -> `14.1.1`,
+/// `go version go1.22.3 linux/amd64` -> `1.22.3`,
+/// `openjdk version "21.0.3" 2024-04-16` -> `21.0.3`.
+fn parse_version(output: &str) -> Option<String> {
+    let line = output.lines().find(|l| !l.trim().is_empty())?;
+    // gcc puts the distribution's package version in parentheses before
+    // its own, rustc puts the commit hash and date after it
+    line.rsplit_once(')')
+        .and_then(|(_, rest)| first_version(rest))
+        .or_else(|| first_version(line))
+}
+
+fn first_version(text: &str) -> Option<String> {
+    text.split_whitespace()
+        .map(|token| {
+            token
+                .trim_matches('"')
+                .trim_start_matches("go")
+                .trim_start_matches('v')
+        })
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))
+        .map(|token| {
+            token
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '.')
+                .collect::<String>()
+                .trim_end_matches('.')
+                .to_string()
+        })
+}
+
+fn manager_state(path: &Path, real: &Path) -> Vec<PathBuf> {
+    let home = dirs::home_dir().unwrap_or_default();
+    let env_dir = |var: &str, default: &str| {
+        std::env::var_os(var)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(default))
+    };
+
+    if real.file_name().is_some_and(|n| n == "rustup") {
+        let rustup = env_dir("RUSTUP_HOME", ".rustup");
+        vec![rustup.join("settings.toml"), rustup.join("toolchains")]
+    } else if path.parent().is_some_and(|dir| dir.ends_with("shims")) {
+        // pyenv, rbenv and friends: the global version file and the
+        // installed versions
+        let root = path.parent().and_then(Path::parent).unwrap_or(path);
+        vec![root.join("version"), root.join("versions")]
+    } else {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_versions() {
+        let cases = [
+            ("gcc (GCC) 14.1.1 20240522\nCopyright (C)", "14.1.1"),
+            ("gcc (Debian 12.2.0-14) 12.2.0", "12.2.0"),
+            ("rustc 1.80.0 (051478957 2024-07-21)", "1.80.0"),
+            ("cargo 1.80.0 (376290515 2024-07-16)", "1.80.0"),
+            ("clang version 18.1.8\nTarget: x86_64", "18.1.8"),
+            ("go version go1.22.5 linux/amd64", "1.22.5"),
+            ("openjdk version \"21.0.4\" 2024-07-16", "21.0.4"),
+            ("Python 3.12.4", "3.12.4"),
+            ("\nv20.1.0\n", "20.1.0"),
+        ];
+        for (output, version) in cases {
+            assert_eq!(
+                parse_version(output).as_deref(),
+                Some(version),
+                "{}",
+                output
+            );
+        }
+        assert_eq!(parse_version("command not found"), None);
+        assert_eq!(parse_version(""), None);
+    }
+}
@@ -0,0 +1,128 @@
+// Size units
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+/// Unit system used when printing byte sizes.
+///
+/// `Iec` divides by 1024 and labels values KiB/MiB/GiB/TiB,
+/// `Si` divides by 1000 and labels values kB/MB/GB/TB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Units {
+    Iec,
+    Si,
+}
+
+impl Units {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "iec" | "binary" => Some(Units::Iec),
+            "si" | "decimal" => Some(Units::Si),
+            _ => None,
+        }
+    }
+
+    fn base(self) -> f64 {
+        match self {
+            Units::Iec => 1024.0,
+            Units::Si => 1000.0,
+        }
+    }
+
+    fn labels(self) -> [&'static str; 5] {
+        match self {
+            Units::Iec => ["B", "KiB", "MiB", "GiB", "TiB"],
+            Units::Si => ["B", "kB", "MB", "GB", "TB"],
+        }
+    }
+
+    /// Picks the largest unit that keeps `bytes` at or above 1.
+    fn scale_for(self, bytes: u64) -> usize {
+        let base = self.base();
+        let mut value = bytes as f64;
+        let mut index = 0;
+        while value >= base && index < self.labels().len() - 1 {
+            value /= base;
+            index += 1;
+        }
+        index
+    }
+
+    fn value_at(self, bytes: u64, index: usize) -> f64 {
+        bytes as f64 / self.base().powi(index as i32)
+    }
+}
+
+/// Formats a single size, e.g. `15.54 GiB`.
+pub fn format_size(bytes: u64, units: Units) -> String {
+    let index = units.scale_for(bytes);
+    if index == 0 {
+        return format!("{} B", bytes);
+    }
+    format!(
+        "{:.2} {}",
+        units.value_at(bytes, index),
+        units.labels()[index]
+    )
+}
+
+/// Formats a used/total pair in the unit of the total, e.g. `3.21 / 15.54 GiB`.
+pub fn format_size_pair(used: u64, total: u64, units: Units) -> String {
+    let index = units.scale_for(total);
+    if index == 0 {
+        return format!("{} / {} B", used, total);
+    }
+    format!(
+        "{:.2} / {:.2} {}",
+        units.value_at(used, index),
+        units.value_at(total, index),
+        units.labels()[index]
+    )
+}
+
+pub fn percent(used: u64, total: u64) -> f64 {
+    if total > 0 {
+        (used as f64 * 100.0) / total as f64
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_unit_names() {
+        assert_eq!(Units::parse("IEC"), Some(Units::Iec));
+        assert_eq!(Units::parse(" binary "), Some(Units::Iec));
+        assert_eq!(Units::parse("si"), Some(Units::Si));
+        assert_eq!(Units::parse("decimal"), Some(Units::Si));
+        assert_eq!(Units::parse("gb"), None);
+    }
+
+    #[test]
+    fn formats_sizes() {
+        assert_eq!(format_size(0, Units::Iec), "0 B");
+        assert_eq!(format_size(1023, Units::Iec), "1023 B");
+        assert_eq!(format_size(1024, Units::Iec), "1.00 KiB");
+        assert_eq!(format_size(1000, Units::Si), "1.00 kB");
+        assert_eq!(format_size(16_684_539_904, Units::Iec), "15.54 GiB");
+        assert_eq!(format_size(16_684_539_904, Units::Si), "16.68 GB");
+        // Stays at the largest unit instead of running out of labels
+        assert_eq!(format_size(u64::MAX, Units::Si), "18446744.07 TB");
+    }
+
+    #[test]
+    fn pairs_use_the_unit_of_the_total() {
+        assert_eq!(
+            format_size_pair(512 * 1024 * 1024, 2 * 1024 * 1024 * 1024, Units::Iec),
+            "0.50 / 2.00 GiB"
+        );
+        assert_eq!(format_size_pair(10, 900, Units::Si), "10 / 900 B");
+    }
+
+    #[test]
+    fn percent_of_zero_total_is_zero() {
+        assert_eq!(percent(5, 0), 0.0);
+        assert_eq!(percent(1, 4), 25.0);
+    }
+}
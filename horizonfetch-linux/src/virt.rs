@@ -0,0 +1,165 @@
+// Virtualization and container detection
+// AnmiTaliDev <anmitalidev@nuros.org>
+
+use crate::system::read_dmi;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+#[derive(Serialize)]
+pub struct VirtInfo {
+    pub hypervisor: Option<String>,
+    pub container: Option<String>,
+}
+
+impl VirtInfo {
+    pub fn detect() -> Self {
+        VirtInfo {
+            hypervisor: detect_hypervisor(),
+            container: detect_container(),
+        }
+    }
+}
+
+/// Known DMI vendor/product substrings and the hypervisor they belong to.
+/// KVM is listed before QEMU because KVM guests often report both.
+const DMI_HYPERVISORS: [(&str, &str); 10] = [
+    ("kvm", "KVM"),
+    ("amazon ec2", "KVM"),
+    ("google compute engine", "KVM"),
+    ("qemu", "QEMU"),
+    ("vmware", "VMware"),
+    ("virtualbox", "VirtualBox"),
+    ("innotek", "VirtualBox"),
+    ("xen", "Xen"),
+    ("bochs", "Bochs"),
+    ("parallels", "Parallels"),
+];
+
+fn detect_hypervisor() -> Option<String> {
+    if fs::read_to_string("/sys/hypervisor/type").is_ok_and(|t| t.trim() == "xen") {
+        return Some("Xen".to_string());
+    }
+
+    let dmi: Vec<String> = ["sys_vendor", "product_name", "bios_vendor", "board_vendor"]
+        .iter()
+        .filter_map(|field| read_dmi(field))
+        .map(|value| value.to_lowercase())
+        .collect();
+
+    for (needle, name) in DMI_HYPERVISORS {
+        if dmi.iter().any(|value| value.contains(needle)) {
+            return Some(name.to_string());
+        }
+    }
+
+    // Hyper-V guests report Microsoft as the vendor of a "Virtual Machine"
+    if dmi.iter().any(|v| v.contains("microsoft")) && dmi.iter().any(|v| v == "virtual machine") {
+        return Some("Hyper-V".to_string());
+    }
+
+    // No DMI match, but the CPU still tells us we run under a hypervisor
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+    let has_flag = cpuinfo
+        .lines()
+        .find(|line| line.starts_with("flags"))
+        .is_some_and(|line| line.split_whitespace().any(|flag| flag == "hypervisor"));
+    if has_flag {
+        return Some("Unknown hypervisor".to_string());
+    }
+
+    None
+}
+
+fn detect_container() -> Option<String> {
+    let osrelease = fs::read_to_string("/proc/sys/kernel/osrelease").unwrap_or_default();
+    if osrelease.contains("WSL2") {
+        return Some("WSL2".to_string());
+    }
+    if osrelease.to_lowercase().contains("microsoft") || std::env::var("WSL_DISTRO_NAME").is_ok() {
+        return Some("WSL".to_string());
+    }
+
+    if Path::new("/.flatpak-info").exists() || std::env::var("FLATPAK_ID").is_ok() {
+        return Some("Flatpak".to_string());
+    }
+    if Path::new("/.dockerenv").exists() {
+        return Some("Docker".to_string());
+    }
+    if Path::new("/run/.containerenv").exists() {
+        return Some("Podman".to_string());
+    }
+
+    // systemd and most container managers publish the manager name here,
+    // /proc/1/environ is only readable inside the container itself
+    let manager = fs::read_to_string("/run/systemd/container")
+        .ok()
+        .map(|s| s.trim().to_string())
+        .or_else(|| {
+            let environ = fs::read("/proc/1/environ").ok()?;
+            environ
+                .split(|&b| b == 0)
+                .filter_map(|var| std::str::from_utf8(var).ok())
+                .find_map(|var| var.strip_prefix("container="))
+                .map(String::from)
+        })
+        .filter(|m| !m.is_empty());
+    if let Some(manager) = manager {
+        return Some(container_name(&manager));
+    }
+
+    let cgroup = fs::read_to_string("/proc/1/cgroup").unwrap_or_default();
+    container_from_cgroup(&cgroup).map(String::from)
+}
+
+/// Recognizes the runtime from the cgroup path of PID 1 on cgroup v1 hosts.
+fn container_from_cgroup(cgroup: &str) -> Option<&'static str> {
+    [
+        ("/docker", "Docker"),
+        ("libpod", "Podman"),
+        ("/lxc", "LXC"),
+        ("kubepods", "Kubernetes"),
+        ("machine.slice/machine-", "systemd-nspawn"),
+    ]
+    .into_iter()
+    .find(|(needle, _)| cgroup.contains(needle))
+    .map(|(_, name)| name)
+}
+
+fn container_name(manager: &str) -> String {
+    match manager {
+        "docker" => "Docker",
+        "podman" => "Podman",
+        "lxc" | "lxc-libvirt" => "LXC",
+        "systemd-nspawn" => "systemd-nspawn",
+        "oci" => "OCI container",
+        "wsl" => "WSL",
+        other => other,
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_container_managers() {
+        assert_eq!(container_name("lxc-libvirt"), "LXC");
+        assert_eq!(container_name("oci"), "OCI container");
+        assert_eq!(container_name("rkt"), "rkt");
+    }
+
+    #[test]
+    fn detects_runtime_from_cgroup() {
+        assert_eq!(
+            container_from_cgroup("12:memory:/docker/4f2a9c\n"),
+            Some("Docker")
+        );
+        assert_eq!(
+            container_from_cgroup("1:name=systemd:/kubepods/besteffort/pod1\n"),
+            Some("Kubernetes")
+        );
+        assert_eq!(container_from_cgroup("0::/init.scope\n"), None);
+    }
+}
@@ -55,6 +55,73 @@ struct Config {
     show_vram_gb: bool,
     show_ram_ext_info: bool,
     show_color_scheme: bool,
+    ram_units: Units,
+    swap_units: Units,
+    disk_units: Units,
+    vram_units: Units,
+}
+
+// KiB/MiB/GiB (1024) or kB/MB/GB (1000)
+#[derive(Clone, Copy)]
+enum Units {
+    Iec,
+    Si,
+}
+
+impl Units {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "iec" | "binary" => Some(Units::Iec),
+            "si" | "decimal" => Some(Units::Si),
+            _ => None,
+        }
+    }
+
+    fn base(self) -> f64 {
+        match self {
+            Units::Iec => 1024.0,
+            Units::Si => 1000.0,
+        }
+    }
+
+    fn labels(self) -> [&'static str; 5] {
+        match self {
+            Units::Iec => ["B", "KiB", "MiB", "GiB", "TiB"],
+            Units::Si => ["B", "kB", "MB", "GB", "TB"],
+        }
+    }
+
+    fn scale_for(self, bytes: u64) -> usize {
+        let mut value = bytes as f64;
+        let mut index = 0;
+        while value >= self.base() && index < self.labels().len() - 1 {
+            value /= self.base();
+            index += 1;
+        }
+        index
+    }
+
+    fn value_at(self, bytes: u64, index: usize) -> f64 {
+        bytes as f64 / self.base().powi(index as i32)
+    }
+}
+
+// 15.54 GiB
+fn format_size(bytes: u64, units: Units) -> String {
+    let index = units.scale_for(bytes);
+    if index == 0 {
+        return format!("{} B", bytes);
+    }
+    format!("{:.2} {}", units.value_at(bytes, index), units.labels()[index])
+}
+
+// 3.21 / 15.54 GiB (unit of the total)
+fn format_size_pair(used: u64, total: u64, units: Units) -> String {
+    let index = units.scale_for(total);
+    if index == 0 {
+        return format!("{} / {} B", used, total);
+    }
+    format!("{:.2} / {:.2} {}", units.value_at(used, index), units.value_at(total, index), units.labels()[index])
 }
 
 #[derive(Deserialize, Debug)]
@@ -114,6 +181,10 @@ impl Default for Config {
             show_vram_gb: false,
             show_ram_ext_info: false,
             show_color_scheme: true,
+            ram_units: Units::Iec,
+            swap_units: Units::Iec,
+            disk_units: Units::Iec,
+            vram_units: Units::Iec,
         }
     }
 }
@@ -141,7 +212,12 @@ impl Config {
         let show_vram_gb = extract_color_param(&content, "show_vram_gb").map_or(false, |v| v == "true");
         let show_ram_ext_info = extract_color_param(&content, "show_ram_ext_info").map_or(false, |v| v == "true");
         let show_color_scheme = extract_color_param(&content, "show_color_scheme").map_or(true, |v| v == "true");
-    Ok(Config { ascii_art, color, info_color, title_color, show_user, show_os, show_uptime, show_shell, show_de, show_screen, show_motherboard, show_cpu, show_gpu, show_ram, show_swap, show_locale, show_disk, show_vram_gb, show_ram_ext_info, show_color_scheme })
+        let units = extract_color_param(&content, "units").and_then(Units::parse).unwrap_or(Units::Iec);
+        let ram_units = extract_color_param(&content, "ram_units").and_then(Units::parse).unwrap_or(units);
+        let swap_units = extract_color_param(&content, "swap_units").and_then(Units::parse).unwrap_or(units);
+        let disk_units = extract_color_param(&content, "disk_units").and_then(Units::parse).unwrap_or(units);
+        let vram_units = extract_color_param(&content, "vram_units").and_then(Units::parse).unwrap_or(units);
+    Ok(Config { ascii_art, color, info_color, title_color, show_user, show_os, show_uptime, show_shell, show_de, show_screen, show_motherboard, show_cpu, show_gpu, show_ram, show_swap, show_locale, show_disk, show_vram_gb, show_ram_ext_info, show_color_scheme, ram_units, swap_units, disk_units, vram_units })
     }
 }
 
@@ -292,23 +368,22 @@ fn get_motherboard_name() -> Option<String> {
     None
 }
 
-fn get_used_ram_gb() -> Result<f64, Box<dyn std::error::Error>> {
+fn get_used_ram_bytes() -> Result<u64, Box<dyn std::error::Error>> {
     let mut mem_status = MEMORYSTATUSEX {
         dwLength: std::mem::size_of::<MEMORYSTATUSEX>() as u32,
         ..Default::default()
     };
     unsafe { GlobalMemoryStatusEx(&mut mem_status)?; }
-    Ok((mem_status.ullTotalPhys - mem_status.ullAvailPhys) as f64 / 1_073_741_824.0)
+    Ok(mem_status.ullTotalPhys - mem_status.ullAvailPhys)
 }
 
-fn get_swap_total_gb() -> f64 {
+fn get_swap_total_bytes() -> u64 {
     let mut mem_status = MEMORYSTATUSEX {
         dwLength: std::mem::size_of::<MEMORYSTATUSEX>() as u32,
         ..Default::default()
     };
     unsafe { let _ = GlobalMemoryStatusEx(&mut mem_status); }
-    let swap_total = mem_status.ullTotalPageFile.saturating_sub(mem_status.ullTotalPhys);
-    swap_total as f64 / 1_073_741_824.0
+    mem_status.ullTotalPageFile.saturating_sub(mem_status.ullTotalPhys)
 }
 
 fn get_drive_usage(drive: &str) -> (u64, u64) {
@@ -324,9 +399,7 @@ fn get_drive_usage(drive: &str) -> (u64, u64) {
         )
         .ok();
     }
-    let total_gb = total_bytes / 1_073_741_824;
-    let used_gb = (total_bytes - free_bytes) / 1_073_741_824;
-    (used_gb, total_gb)
+    (total_bytes.saturating_sub(free_bytes), total_bytes)
 }
 
 fn get_parent_pid(pid: u32) -> Option<u32> {
@@ -607,16 +680,16 @@ fn main() -> io::Result<()> {
             .map(|&c| char::from_u32(c as u32).unwrap_or('?'))
             .collect();
         if !gpu_name.contains("Microsoft Basic Render Driver") {
-    let vram_gb = desc.DedicatedVideoMemory as f64 / (1024.0 * 1024.0 * 1024.0);
+    let vram = format_size(desc.DedicatedVideoMemory as u64, CONFIG.vram_units);
     let gpu_line = if CONFIG.show_vram_gb {
         format!(
-            "\x1b[{}m{}\x1b[0m \x1b[{}m{}\x1b[0m \x1b[{}m{:.2}GB\x1b[0m",
+            "\x1b[{}m{}\x1b[0m \x1b[{}m{}\x1b[0m \x1b[{}m{}\x1b[0m",
             title_color,
             if i == 0 { "Gpu:" } else { "    " },
             info_color,
             gpu_name.trim(),
             info_color,
-            vram_gb
+            vram
         )
     } else {
         format!(
@@ -658,20 +731,20 @@ fn main() -> io::Result<()> {
     (total + cap, speed.max(spd), count + (cap > 0) as u32)
 });
 
-    let total_gb = total_bytes as f64 / 1_073_741_824.0;
-    let used_gb = get_used_ram_gb().expect("RAM info failed") as f64;
-    let percent = if total_gb > 0.0 { used_gb * 100.0 / total_gb } else { 0.0 };
-    let size_per_module = if modules > 0 { total_gb / modules as f64 } else { 0.0 };
+    let used_bytes = get_used_ram_bytes().expect("RAM info failed");
+    let percent = if total_bytes > 0 { used_bytes as f64 * 100.0 / total_bytes as f64 } else { 0.0 };
+    let size_per_module = if modules > 0 { total_bytes / modules as u64 } else { 0 };
+    let ram_str = format_size_pair(used_bytes, total_bytes, CONFIG.ram_units);
 
     let output = if modules > 0 && CONFIG.show_ram_ext_info {
     format!(
-        "\x1b[{}mRam:\x1b[0m \x1b[{}m{:.2}\x1b[0m \x1b[97m/\x1b[0m \x1b[{}m{:.2}gb ({:.0}%) ({}x{:.0}gb, {} MHz)\x1b[0m",
-        title_color, info_color, used_gb, info_color, total_gb, percent, modules, size_per_module, max_speed
+        "\x1b[{}mRam:\x1b[0m \x1b[{}m{} ({:.0}%) ({}x{}, {} MHz)\x1b[0m",
+        title_color, info_color, ram_str, percent, modules, format_size(size_per_module, CONFIG.ram_units), max_speed
     )
     } else {
     format!(
-        "\x1b[{}mRam:\x1b[0m \x1b[{}m{:.2}\x1b[0m \x1b[97m/\x1b[0m \x1b[{}m{:.2}gb ({:.0}%)\x1b[0m",
-        title_color, info_color, used_gb, info_color, total_gb, percent
+        "\x1b[{}mRam:\x1b[0m \x1b[{}m{} ({:.0}%)\x1b[0m",
+        title_color, info_color, ram_str, percent
     )
     };
 
@@ -688,7 +761,7 @@ fn main() -> io::Result<()> {
     execute!(
     io::stdout(),
     MoveTo(art_width as u16, info_y),
-    Print(format!("\x1b[{}mSwap:\x1b[0m \x1b[{}m{:.2}gb\x1b[0m",title_color, info_color, get_swap_total_gb()))
+    Print(format!("\x1b[{}mSwap:\x1b[0m \x1b[{}m{}\x1b[0m",title_color, info_color, format_size(get_swap_total_bytes(), CONFIG.swap_units)))
 )?;
     info_y += 1;
 }
@@ -737,13 +810,11 @@ fn main() -> io::Result<()> {
             }
             let percent = (used as f64 / total as f64 * 100.0).round() as u64;
             let line = format!(
-                "\x1b[{}mDisk:\x1b[0m \x1b[97m{:<width$}\x1b[0m \x1b[{}m{:>3}gb\x1b[0m \x1b[97m/\x1b[0m \x1b[{}m{:>3}gb ({}%)\x1b[0m",
+                "\x1b[{}mDisk:\x1b[0m \x1b[97m{:<width$}\x1b[0m \x1b[{}m{} ({}%)\x1b[0m",
                 title_color,
                 drive,
                 info_color,
-                used,
-                info_color,
-                total,
+                format_size_pair(used, total, CONFIG.disk_units),
                 percent,
                 width = max_len
             );